@@ -0,0 +1,2 @@
+pub mod move_generator;
+pub mod solver;
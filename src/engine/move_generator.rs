@@ -0,0 +1,129 @@
+//! Generation of the moves that can be made in a game state.
+
+use crate::model::game::GameState;
+use crate::model::game_type::GameType;
+use crate::model::moves::Move;
+use crate::model::play_rules::trick_winner;
+use crate::model::ruleset::Ruleset;
+
+/// Moves that can be made in `state` under `rules` during the Lizitation and once the cards have
+/// been played out.
+///
+/// A complete stich is taken by its winner, otherwise the player whose turn it is plays a card.
+pub fn next_moves(state: &GameState, rules: &Ruleset) -> Vec<Move> {
+    if state.bidding.is_some() {
+        return bid_moves(state, rules);
+    }
+    if state.stich.is_full() {
+        return trick_winner(&state.stich, state.leader(), state)
+            .map(|player| vec![Move::TakeStich { player }])
+            .unwrap_or_default();
+    }
+    play_moves(state, rules)
+}
+
+/// Bids of games `rules` allow and the pass of the player whose turn it is in the Lizitation.
+pub fn bid_moves(state: &GameState, rules: &Ruleset) -> Vec<Move> {
+    let Some(bidding) = state.bidding else {
+        return Vec::new();
+    };
+    let player = bidding.turn;
+    let pass = bidding
+        .check_pass(player)
+        .is_ok()
+        .then_some(Move::Pass { player });
+    pass.into_iter()
+        .chain(
+            GameType::ALL
+                .into_iter()
+                .filter(|&typ| rules.allows(typ) && bidding.check_bid(player, typ).is_ok())
+                .map(|typ| Move::Bid { player, typ }),
+        )
+        .collect()
+}
+
+/// Moves playing a card into the current stich.
+///
+/// Only known cards are generated, for a hidden hand every card it could legally play.
+pub fn play_moves(state: &GameState, rules: &Ruleset) -> Vec<Move> {
+    let player = state.current_player();
+    state
+        .legal_plays(player, rules)
+        .iter()
+        .map(|card| Move::Play { player, card })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::card::Card;
+    use std::str::FromStr;
+
+    #[test]
+    fn play_moves_follow() {
+        let game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#HBT22T21XD/#............/#............../#KK R1XK-1 1 /// - -",
+        )
+        .unwrap();
+        assert_eq!(
+            play_moves(&game_state, &Ruleset::default()),
+            vec![
+                Move::Play {
+                    player: 1,
+                    card: Card::T21
+                },
+                Move::Play {
+                    player: 1,
+                    card: Card::T22
+                },
+            ]
+        );
+        for mv in play_moves(&game_state, &Ruleset::default()) {
+            assert!(game_state.clone().apply(&mv, &Ruleset::default()).is_ok());
+        }
+    }
+
+    #[test]
+    fn next_moves_take_stich() {
+        let game_state = GameState::from_str(
+            "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HKX10X9#.........../#.........../#.........../#HDH1HBT22 R1XK-1 - /// - -",
+        )
+        .unwrap();
+        assert_eq!(
+            next_moves(&game_state, &Ruleset::default()),
+            vec![Move::TakeStich { player: 3 }]
+        );
+    }
+
+    #[test]
+    fn bid_moves_in_lizitation() {
+        let mut game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#............/#............/#............/#............/# L1:-/-/-/- - /// - -",
+        )
+        .unwrap();
+        // Vorhand has to open with any game
+        let moves = next_moves(&game_state, &Ruleset::default());
+        assert_eq!(moves.len(), GameType::ALL.len());
+        assert_eq!(
+            moves[1],
+            Move::Bid {
+                player: 0,
+                typ: GameType::R
+            }
+        );
+
+        game_state.apply(&moves[1], &Ruleset::default()).unwrap();
+        let moves = next_moves(&game_state, &Ruleset::grazer());
+        assert_eq!(moves[0], Move::Pass { player: 1 });
+        assert!(!moves.contains(&Move::Bid {
+            player: 1,
+            typ: GameType::S
+        }));
+        assert!(moves.contains(&Move::Bid {
+            player: 1,
+            typ: GameType::SPD
+        }));
+        assert!(play_moves(&game_state, &Ruleset::default()).is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! Double-dummy solver, finding optimal moves when every card is known.
+//!
+//! The solver searches all legal plays until the end of the game with alpha-beta pruning. Both
+//! sides play perfectly and see all cards. Positions reached through different orders of
+//! stiche are only searched once.
+
+use std::collections::HashMap;
+
+use super::move_generator::next_moves;
+use crate::model::card_set::CardSet;
+use crate::model::counting::{Points, BLATT_PER_POINT};
+use crate::model::game::{GameState, NUM_PLAYERS};
+use crate::model::moves::Move;
+use crate::model::pile::Pile;
+use crate::model::ruleset::Ruleset;
+use crate::model::stich::Stich;
+
+/// Result of the game for the declarer's team with optimal play of both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// Card points of the declarer's team in a positive game.
+    Points(Points),
+    /// Stiche the declarer makes in a Bettler or Piccolo game.
+    Stiche(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Solution {
+    /// Best move in the solved state, `None` if the game is finished.
+    pub best_move: Option<Move>,
+    pub outcome: Outcome,
+}
+
+/// Solves `state` under `rules`, `None` if it cannot be solved.
+///
+/// Every card has to be known, the game has to be declared by a single player whose partner is
+/// known, and the talon has to be exchanged. Trischaken is not solved, since it has no declarer
+/// side.
+///
+/// In positive games the declarer's team tries to make as many card points as possible. In
+/// Bettler and Piccolo games the declarer tries to make exactly the required number of stiche,
+/// and the opponents try to make them miss it by as many stiche as possible.
+pub fn solve(state: &GameState, rules: &Ruleset) -> Option<Solution> {
+    if state.bidding.is_some()
+        || Pile::ALL
+            .iter()
+            .any(|&pile| state.pile(pile).unknown_len() > 0)
+        || (0..NUM_PLAYERS).any(|player| state.cards_to_lay_down(player) > 0)
+    {
+        return None;
+    }
+    let [declarer] = state.declarers()[..] else {
+        return None;
+    };
+    let typ = state.players[declarer].calls.typ?;
+    let objective = match typ.required_stiche() {
+        Some(required) => Objective::Stiche { required },
+        None if typ.is_positive() => Objective::Points,
+        None => return None,
+    };
+    let team = state.declarer_team(declarer)?;
+
+    let mut solver = Solver {
+        rules,
+        declarer,
+        team: std::array::from_fn(|player| team.contains(&player)),
+        objective,
+        table: HashMap::new(),
+    };
+    let made = state.stiche_count(declarer);
+    let mut root = *state;
+    let (value, best_move) = solver.search(&mut root, made, i32::MIN, i32::MAX);
+
+    let outcome = match objective {
+        Objective::Points => {
+            Outcome::Points(state.team_points(&team)? + Points::new(0, value as u16))
+        }
+        Objective::Stiche { .. } => Outcome::Stiche(solver.principal_stiche(state, made)),
+    };
+    Some(Solution { best_move, outcome })
+}
+
+/// What the declarer's team maximizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Objective {
+    /// Blatt of the stiche the team makes.
+    Points,
+    /// The negative difference between the stiche the declarer makes and `required`.
+    Stiche { required: usize },
+}
+
+/// Position of the game as far as the rest of the search is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    hands: [CardSet; NUM_PLAYERS],
+    stich: Stich,
+    leader: usize,
+    /// Stiche the declarer made so far, only for [`Objective::Stiche`].
+    made: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    value: i32,
+    bound: Bound,
+    best_move: Option<Move>,
+}
+
+struct Solver<'a> {
+    rules: &'a Ruleset,
+    declarer: usize,
+    /// Players on the side of the declarer.
+    team: [bool; NUM_PLAYERS],
+    objective: Objective,
+    table: HashMap<Position, Entry>,
+}
+
+impl Solver<'_> {
+    /// Value of `state` for the declarer's team, searched within `alpha` and `beta`, and the
+    /// best move. For [`Objective::Points`] only stiche taken from now on are counted.
+    fn search(
+        &mut self,
+        state: &mut GameState,
+        made: usize,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> (i32, Option<Move>) {
+        if state.is_finished() {
+            return (self.final_value(made), None);
+        }
+
+        let position = Position {
+            hands: state.players.map(|player| player.hand.cards),
+            stich: state.stich,
+            leader: state.leader(),
+            made: match self.objective {
+                Objective::Points => 0,
+                Objective::Stiche { .. } => made,
+            },
+        };
+        if let Some(entry) = self.table.get(&position) {
+            match entry.bound {
+                Bound::Exact => return (entry.value, entry.best_move),
+                Bound::Lower => alpha = alpha.max(entry.value),
+                Bound::Upper => beta = beta.min(entry.value),
+            }
+            if alpha >= beta {
+                return (entry.value, entry.best_move);
+            }
+        }
+
+        let (original_alpha, original_beta) = (alpha, beta);
+        let maximizing = self.team[state.current_player()];
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+        let mut best_move = None;
+        let moves = next_moves(state, self.rules);
+        if moves.is_empty() {
+            return (self.final_value(made), None);
+        }
+        for mv in moves {
+            let (gain, made) = self.gain(state, &mv, made);
+            let undo = state.apply(&mv, self.rules).expect("generated move");
+            let value = gain
+                + self
+                    .search(
+                        state,
+                        made,
+                        alpha.saturating_sub(gain),
+                        beta.saturating_sub(gain),
+                    )
+                    .0;
+            state.undo(&undo);
+
+            if (maximizing && value > best) || (!maximizing && value < best) {
+                best = value;
+                best_move = Some(mv);
+            }
+            if maximizing {
+                alpha = alpha.max(best);
+            } else {
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= original_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            position,
+            Entry {
+                value: best,
+                bound,
+                best_move,
+            },
+        );
+        (best, best_move)
+    }
+
+    /// Value `mv` adds for the declarer's team and the stiche the declarer has made after it.
+    fn gain(&self, state: &GameState, mv: &Move, made: usize) -> (i32, usize) {
+        let Move::TakeStich { player } = *mv else {
+            return (0, made);
+        };
+        match self.objective {
+            Objective::Points if self.team[player] => {
+                let points = Points::of(&state.stich.card_set()).unwrap_or_default();
+                let blatt = points.points() * BLATT_PER_POINT + points.blatt();
+                (blatt as i32, made)
+            }
+            Objective::Points => (0, made),
+            Objective::Stiche { .. } => (0, made + usize::from(player == self.declarer)),
+        }
+    }
+
+    fn final_value(&self, made: usize) -> i32 {
+        match self.objective {
+            Objective::Points => 0,
+            Objective::Stiche { required } => -(made.abs_diff(required) as i32),
+        }
+    }
+
+    /// Stiche the declarer makes when both sides keep playing the best moves from `state`.
+    fn principal_stiche(&mut self, state: &GameState, mut made: usize) -> usize {
+        let mut state = *state;
+        while let (_, Some(mv)) = self.search(&mut state, made, i32::MIN, i32::MAX) {
+            made = self.gain(&state, &mv, made).1;
+            state.apply(&mv, self.rules).expect("best move");
+        }
+        made
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::card::Card;
+
+    // Player 1 leads into the last two stiche, player 2 holds HK and H4 and has made no stich
+    const ENDGAME: &str = "T1T2T3/T4T5T6#H1X9/HDHPHBKKKDKPKBK1K2K3K4PKPDPPPBP10P9P8P7XKXDXPXBX10#HKH4/#H2X8/T7T8T9T10T11T12T13T14#H3X7/T15T16T17T18T19T20T21T22# SR2HK-- 1 /// - -";
+
+    #[test]
+    fn solve_positive_game() {
+        // Leading H1 would lose both stiche, so player 1 leads X9 and player 2 keeps the HK
+        let game_state = GameState::from_str(ENDGAME).unwrap();
+        assert_eq!(
+            solve(&game_state, &Ruleset::default()),
+            Some(Solution {
+                best_move: Some(Move::Play {
+                    player: 0,
+                    card: Card::X9
+                }),
+                outcome: Outcome::Points(Points::new(5, 1)),
+            })
+        );
+    }
+
+    #[test]
+    fn solve_negative_game() {
+        // Player 2 has to win H1 with the HK when they have to overtake, and then nobody else
+        // holds Herz for their H4
+        let game_state = GameState::from_str(&ENDGAME.replace("SR2HK--", "B2---")).unwrap();
+        assert_eq!(
+            solve(&game_state, &Ruleset::default()),
+            Some(Solution {
+                best_move: Some(Move::Play {
+                    player: 0,
+                    card: Card::H1
+                }),
+                outcome: Outcome::Stiche(2),
+            })
+        );
+
+        let rules = Ruleset {
+            overtake: false,
+            ..Ruleset::default()
+        };
+        assert_eq!(
+            solve(&game_state, &rules).map(|solution| solution.outcome),
+            Some(Outcome::Stiche(0))
+        );
+    }
+
+    #[test]
+    fn solve_finished_game() {
+        let mut game_state = GameState::from_str(ENDGAME).unwrap();
+        while let Some(Solution {
+            best_move: Some(mv),
+            ..
+        }) = solve(&game_state, &Ruleset::default())
+        {
+            game_state.apply(&mv, &Ruleset::default()).unwrap();
+        }
+        assert!(game_state.is_finished());
+        assert_eq!(
+            solve(&game_state, &Ruleset::default()),
+            Some(Solution {
+                best_move: None,
+                outcome: Outcome::Points(Points::new(5, 1)),
+            })
+        );
+    }
+
+    #[test]
+    fn solve_unsupported() {
+        let rules = Ruleset::default();
+        let hidden = ENDGAME.replace("#H2X8/", "#../");
+        assert_eq!(solve(&GameState::from_str(&hidden).unwrap(), &rules), None);
+        let trischaken = ENDGAME.replace("SR2HK--", "T2---");
+        assert_eq!(
+            solve(&GameState::from_str(&trischaken).unwrap(), &rules),
+            None
+        );
+        let piccolo_bei = ENDGAME.replace("SR2HK--", "PB12PB13---");
+        assert_eq!(
+            solve(&GameState::from_str(&piccolo_bei).unwrap(), &rules),
+            None
+        );
+    }
+}
@@ -1,18 +1,16 @@
 use std::str::FromStr;
 
-use model::game::GameState;
-
-mod model;
+use pagengine::model::game::GameState;
 
 fn main() {
     match GameState::from_str(
-        ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 1K/T// - R12",
+        "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - 1K/T// - R12",
     ) {
         Ok(game_state) => {
             println!("OK: {:?}", game_state);
         }
         Err(e) => {
-            println!("Err: {:?}", e);
+            println!("Err: {e}");
         }
     }
 }
@@ -0,0 +1,123 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::error::{TafError, TafLocation};
+
+pub const NUM_ANSAGEN: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Ansage {
+    Pagat,
+    Uhu,
+    Pelikan,
+    Quapil,
+    Trull,
+    Ultimo,
+    Kings,
+    Valat,
+}
+
+impl Ansage {
+    pub const ALL: [Ansage; NUM_ANSAGEN] = [
+        Ansage::Pagat,
+        Ansage::Uhu,
+        Ansage::Pelikan,
+        Ansage::Quapil,
+        Ansage::Trull,
+        Ansage::Ultimo,
+        Ansage::Kings,
+        Ansage::Valat,
+    ];
+
+    /// Dense index of the Ansage, in TAF notation order.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl FromStr for Ansage {
+    type Err = TafError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "1" => Ok(Ansage::Pagat),
+            "2" => Ok(Ansage::Uhu),
+            "3" => Ok(Ansage::Pelikan),
+            "4" => Ok(Ansage::Quapil),
+            "T" => Ok(Ansage::Trull),
+            "K" => Ok(Ansage::Kings),
+            "U" => Ok(Ansage::Ultimo),
+            "V" => Ok(Ansage::Valat),
+            _ => Err(TafError::InvalidAnsage {
+                location: TafLocation::new(s, 0..s.len()),
+            }),
+        }
+    }
+}
+
+impl Display for Ansage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Ansage::Pagat => '1',
+            Ansage::Uhu => '2',
+            Ansage::Pelikan => '3',
+            Ansage::Quapil => '4',
+            Ansage::Trull => 'T',
+            Ansage::Ultimo => 'U',
+            Ansage::Kings => 'K',
+            Ansage::Valat => 'V',
+        };
+        write!(f, "{c}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansage_from_str() {
+        assert_eq!(Ansage::from_str("1"), Ok(Ansage::Pagat));
+        assert_eq!(Ansage::from_str("2"), Ok(Ansage::Uhu));
+        assert_eq!(Ansage::from_str("3"), Ok(Ansage::Pelikan));
+        assert_eq!(Ansage::from_str("4"), Ok(Ansage::Quapil));
+        assert_eq!(Ansage::from_str("T"), Ok(Ansage::Trull));
+        assert_eq!(Ansage::from_str("K"), Ok(Ansage::Kings));
+        assert_eq!(Ansage::from_str("U"), Ok(Ansage::Ultimo));
+        assert_eq!(Ansage::from_str("V"), Ok(Ansage::Valat));
+
+        assert_eq!(Ansage::from_str("t"), Ok(Ansage::Trull));
+        assert_eq!(Ansage::from_str("v"), Ok(Ansage::Valat));
+
+        assert!(matches!(
+            Ansage::from_str("5"),
+            Err(TafError::InvalidAnsage { .. })
+        ));
+        assert!(matches!(
+            Ansage::from_str("X"),
+            Err(TafError::InvalidAnsage { .. })
+        ));
+        assert!(matches!(
+            Ansage::from_str("TK"),
+            Err(TafError::InvalidAnsage { .. })
+        ));
+    }
+
+    #[test]
+    fn ansage_display() {
+        for ansage in Ansage::ALL {
+            assert_eq!(Ansage::from_str(&ansage.to_string()), Ok(ansage));
+        }
+        assert_eq!(
+            Ansage::ALL.map(|ansage| ansage.to_string()).concat(),
+            "1234TUKV"
+        );
+    }
+
+    #[test]
+    fn ansage_index() {
+        for (index, ansage) in Ansage::ALL.iter().enumerate() {
+            assert_eq!(ansage.index(), index);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Lizitation, the auction deciding who plays which game.
+//!
+//! Vorhand opens by bidding a game, saying "Mein Spiel" bids a Rufer. Then the players take turns
+//! in seating order, each either passing for good or bidding a higher game. While a Picc-, Zwicc-
+//! or Driccolo bei is the highest bid, the other players may join it with a bei of any level. The
+//! Lizitation is over once every player who has not passed holds the highest bid, or a bei while
+//! a bei is highest, and each of them plays the game they bid.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::error::{TafError, TafLocation};
+use super::game::NUM_PLAYERS;
+use super::game_type::GameType;
+
+/// State of an ongoing Lizitation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bidding {
+    /// Game each player bid last, `None` if they have not bid yet or passed.
+    pub bids: [Option<GameType>; NUM_PLAYERS],
+    /// Players who passed and may not bid again.
+    pub passed: [bool; NUM_PLAYERS],
+    /// Player whose turn it is.
+    pub turn: usize,
+}
+
+impl Bidding {
+    /// A Lizitation where Vorhand has to open.
+    pub const fn new() -> Self {
+        Self {
+            bids: [None; NUM_PLAYERS],
+            passed: [false; NUM_PLAYERS],
+            turn: 0,
+        }
+    }
+
+    /// The highest game bid so far.
+    pub fn highest(&self) -> Option<GameType> {
+        self.bids.iter().flatten().max().copied()
+    }
+
+    /// Players who play the game they bid once the Lizitation is over, `None` while it is not.
+    pub fn declarers(&self) -> Option<Vec<usize>> {
+        let highest = self.highest()?;
+        let remaining: Vec<usize> = (0..NUM_PLAYERS)
+            .filter(|&player| !self.passed[player])
+            .collect();
+        remaining
+            .iter()
+            .all(|&player| match self.bids[player] {
+                Some(bid) if highest.allows_multiple_declarers() => bid.allows_multiple_declarers(),
+                bid => bid == Some(highest),
+            })
+            .then_some(remaining)
+    }
+
+    /// Checks that `player` may bid `typ`.
+    ///
+    /// A player who has not bid a bei yet may join a bei that is the highest bid with a bei of any
+    /// level, even a lower one.
+    pub fn check_bid(&self, player: usize, typ: GameType) -> Result<(), &'static str> {
+        self.check_turn(player)?;
+        match self.highest() {
+            Some(highest) if typ > highest => Ok(()),
+            Some(highest)
+                if highest.allows_multiple_declarers()
+                    && typ.allows_multiple_declarers()
+                    && !self.bids[player].is_some_and(|bid| bid.allows_multiple_declarers()) =>
+            {
+                Ok(())
+            }
+            Some(_) => Err("the bid is not higher than the highest bid"),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that `player` may pass.
+    pub fn check_pass(&self, player: usize) -> Result<(), &'static str> {
+        self.check_turn(player)?;
+        if self.highest().is_none() {
+            return Err("Vorhand has to open the Lizitation");
+        }
+        Ok(())
+    }
+
+    /// Bids `typ` for `player`, see [`Bidding::check_bid`].
+    pub fn bid(&mut self, player: usize, typ: GameType) -> Result<(), &'static str> {
+        self.check_bid(player, typ)?;
+        self.bids[player] = Some(typ);
+        self.advance();
+        Ok(())
+    }
+
+    /// Passes for `player`, see [`Bidding::check_pass`].
+    pub fn pass(&mut self, player: usize) -> Result<(), &'static str> {
+        self.check_pass(player)?;
+        self.bids[player] = None;
+        self.passed[player] = true;
+        self.advance();
+        Ok(())
+    }
+
+    fn check_turn(&self, player: usize) -> Result<(), &'static str> {
+        if self.declarers().is_some() {
+            return Err("the Lizitation is over");
+        }
+        if player != self.turn {
+            return Err("it is not the player's turn");
+        }
+        Ok(())
+    }
+
+    /// Gives the turn to the next player who has not passed.
+    fn advance(&mut self) {
+        for offset in 1..=NUM_PLAYERS {
+            let player = (self.turn + offset) % NUM_PLAYERS;
+            if !self.passed[player] {
+                self.turn = player;
+                return;
+            }
+        }
+    }
+}
+
+/// Parses the Lizitation notation of the TAF game group without the leading `L`, e.g. `2:R/-/-/-`.
+impl FromStr for Bidding {
+    type Err = TafError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TafError::InvalidSyntax {
+            location: TafLocation::new(s, 0..s.len()),
+            expected: "the player to bid and four bids separated by `/`",
+        };
+        let (turn, bids) = s.split_once(':').ok_or_else(invalid)?;
+        let turn = match turn.parse::<usize>() {
+            Ok(turn @ 1..=NUM_PLAYERS) => turn - 1,
+            _ => return Err(invalid()),
+        };
+
+        let mut bidding = Bidding {
+            turn,
+            ..Bidding::new()
+        };
+        let bids: Vec<&str> = bids.split('/').collect();
+        if bids.len() != NUM_PLAYERS {
+            return Err(invalid());
+        }
+        for (player, bid) in bids.into_iter().enumerate() {
+            match bid {
+                "-" => {}
+                "x" | "X" => bidding.passed[player] = true,
+                typ => bidding.bids[player] = Some(typ.parse().map_err(|_| invalid())?),
+            }
+        }
+        if bidding.passed[turn] {
+            return Err(invalid());
+        }
+        Ok(bidding)
+    }
+}
+
+impl Display for Bidding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.turn + 1)?;
+        for player in 0..NUM_PLAYERS {
+            if player > 0 {
+                write!(f, "/")?;
+            }
+            match (self.passed[player], self.bids[player]) {
+                (true, _) => write!(f, "x")?,
+                (false, Some(typ)) => write!(f, "{typ}")?,
+                (false, None) => write!(f, "-")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bidding_rufer() {
+        let mut bidding = Bidding::new();
+        assert_eq!(
+            bidding.check_pass(0),
+            Err("Vorhand has to open the Lizitation")
+        );
+        assert_eq!(
+            bidding.check_bid(1, GameType::R),
+            Err("it is not the player's turn")
+        );
+
+        // Mein Spiel
+        bidding.bid(0, GameType::R).unwrap();
+        assert_eq!(bidding.turn, 1);
+        assert_eq!(bidding.declarers(), None);
+        assert_eq!(
+            bidding.check_bid(1, GameType::R),
+            Err("the bid is not higher than the highest bid")
+        );
+        for player in 1..NUM_PLAYERS {
+            bidding.pass(player).unwrap();
+        }
+        assert_eq!(bidding.declarers(), Some(vec![0]));
+        assert_eq!(bidding.highest(), Some(GameType::R));
+        assert_eq!(
+            bidding.check_bid(0, GameType::D),
+            Err("the Lizitation is over")
+        );
+    }
+
+    #[test]
+    fn bidding_raise() {
+        let mut bidding = Bidding::new();
+        bidding.bid(0, GameType::R).unwrap();
+        bidding.bid(1, GameType::SR).unwrap();
+        bidding.pass(2).unwrap();
+        bidding.bid(3, GameType::B).unwrap();
+        bidding.bid(0, GameType::D).unwrap();
+        bidding.pass(1).unwrap();
+        assert_eq!(bidding.turn, 3);
+        bidding.pass(3).unwrap();
+        assert_eq!(bidding.declarers(), Some(vec![0]));
+        assert_eq!(bidding.highest(), Some(GameType::D));
+    }
+
+    #[test]
+    fn bidding_bei() {
+        let mut bidding = Bidding::new();
+        bidding.bid(0, GameType::R).unwrap();
+        bidding.bid(1, GameType::PB2).unwrap();
+        bidding.bid(2, GameType::PB2).unwrap();
+        // A lower bei joins, but no other game
+        assert!(bidding.check_bid(3, GameType::PB1).is_ok());
+        assert_eq!(
+            bidding.check_bid(3, GameType::SR),
+            Err("the bid is not higher than the highest bid")
+        );
+        bidding.pass(3).unwrap();
+        assert_eq!(bidding.declarers(), None);
+        bidding.pass(0).unwrap();
+        assert_eq!(bidding.declarers(), Some(vec![1, 2]));
+
+        // Bei of different levels are played together
+        let mut bidding = Bidding::new();
+        bidding.bid(0, GameType::R).unwrap();
+        bidding.bid(1, GameType::PB3).unwrap();
+        bidding.pass(2).unwrap();
+        bidding.bid(3, GameType::PB1).unwrap();
+        assert_eq!(bidding.declarers(), None);
+        bidding.bid(0, GameType::PB2).unwrap();
+        assert_eq!(bidding.declarers(), Some(vec![0, 1, 3]));
+        assert_eq!(bidding.highest(), Some(GameType::PB3));
+
+        // A higher game has to be outbid or passed by everyone playing a bei
+        let mut bidding = Bidding::new();
+        bidding.bid(0, GameType::PB1).unwrap();
+        bidding.bid(1, GameType::PB2).unwrap();
+        bidding.bid(2, GameType::B).unwrap();
+        bidding.pass(3).unwrap();
+        assert_eq!(
+            bidding.check_bid(0, GameType::PB3),
+            Err("the bid is not higher than the highest bid")
+        );
+        bidding.pass(0).unwrap();
+        bidding.pass(1).unwrap();
+        assert_eq!(bidding.declarers(), Some(vec![2]));
+    }
+
+    #[test]
+    fn bidding_notation() {
+        let mut bidding = Bidding::new();
+        assert_eq!(bidding.to_string(), "1:-/-/-/-");
+        bidding.bid(0, GameType::R).unwrap();
+        bidding.pass(1).unwrap();
+        bidding.bid(2, GameType::PB1).unwrap();
+        assert_eq!(bidding.to_string(), "4:R/x/PB1/-");
+        assert_eq!(Bidding::from_str("4:R/x/PB1/-"), Ok(bidding));
+        assert_eq!(Bidding::from_str("4:r/X/pb1/-"), Ok(bidding));
+
+        for invalid in [
+            "",
+            "4",
+            "5:-/-/-/-",
+            "1:-/-/-",
+            "1:-/-/-/-/-",
+            "1:Q/-/-/-",
+            "2:-/x/-/-",
+        ] {
+            assert!(matches!(
+                Bidding::from_str(invalid),
+                Err(TafError::InvalidSyntax { .. })
+            ));
+        }
+    }
+}
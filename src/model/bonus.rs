@@ -0,0 +1,449 @@
+//! Evaluation of Prämien, the bonuses that are scored in addition to the game.
+//!
+//! Every [`Ansage`] is also a bonus that can be made silently. Announced bonuses count double, and
+//! a side that announces a bonus pays for it if it fails. Once Valat is announced or made, it is
+//! the only thing that is scored.
+
+use super::ansage::Ansage;
+use super::card::Card;
+use super::card_set::CardSet;
+use super::game::{GameState, HAND_SIZE, NUM_PLAYERS};
+use super::ruleset::Ruleset;
+use super::spritzen::SpritzenTarget;
+use super::stich::TakenStich;
+
+/// Base value of each bonus when made silently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BonusValues {
+    pub pagat: u32,
+    pub uhu: u32,
+    pub pelikan: u32,
+    pub quapil: u32,
+    pub trull: u32,
+    pub kings: u32,
+    pub ultimo: u32,
+    /// Factor the value of the game is multiplied with.
+    pub valat: u32,
+}
+
+impl BonusValues {
+    pub fn get(&self, ansage: Ansage) -> u32 {
+        match ansage {
+            Ansage::Pagat => self.pagat,
+            Ansage::Uhu => self.uhu,
+            Ansage::Pelikan => self.pelikan,
+            Ansage::Quapil => self.quapil,
+            Ansage::Trull => self.trull,
+            Ansage::Kings => self.kings,
+            Ansage::Ultimo => self.ultimo,
+            Ansage::Valat => self.valat,
+        }
+    }
+}
+
+impl Default for BonusValues {
+    fn default() -> Self {
+        Self {
+            pagat: 1,
+            uhu: 2,
+            pelikan: 3,
+            quapil: 4,
+            trull: 1,
+            kings: 1,
+            ultimo: 2,
+            valat: 4,
+        }
+    }
+}
+
+/// One of the two sides of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Declarers,
+    Opponents,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BonusOutcome {
+    Achieved,
+    Failed,
+    /// The bonus was announced, but the other side made it.
+    AchievedByOpponents,
+}
+
+/// A bonus scored for one side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bonus {
+    pub ansage: Ansage,
+    /// The side that announced the bonus or made it silently.
+    pub side: Side,
+    pub announced: bool,
+    pub outcome: BonusOutcome,
+    /// Factor from Spritzen against the announcement, 1 for silent bonuses.
+    pub multiplier: u32,
+}
+
+impl Bonus {
+    /// Amount every opponent of `side` pays, negative if `side` pays.
+    ///
+    /// `game_value` is the base value of the game, which Valat is a multiple of.
+    pub fn value(&self, values: &BonusValues, game_value: u32) -> i32 {
+        let mut value = values.get(self.ansage) * self.multiplier;
+        if self.ansage == Ansage::Valat {
+            value *= game_value;
+        }
+        if self.announced {
+            value *= 2;
+        }
+        match self.outcome {
+            BonusOutcome::Achieved => value as i32,
+            BonusOutcome::Failed | BonusOutcome::AchievedByOpponents => -(value as i32),
+        }
+    }
+}
+
+impl GameState {
+    /// Side `player` plays on.
+    pub fn side(&self, player: usize) -> Option<Side> {
+        let declarer = *self.declarers().first()?;
+        Some(if self.declarer_team(declarer)?.contains(&player) {
+            Side::Declarers
+        } else {
+            Side::Opponents
+        })
+    }
+
+    /// Bonuses of a finished game with the stiche of `history`, `None` if the game is not
+    /// finished or the bonuses depend on unknown cards.
+    ///
+    /// Negative games and Trischaken have no bonuses.
+    pub fn bonuses(&self, history: &[TakenStich]) -> Option<Vec<Bonus>> {
+        if !self.is_finished() || history.len() != HAND_SIZE {
+            return None;
+        }
+        if !self.game_type()?.is_positive() {
+            return Some(Vec::new());
+        }
+
+        let valat = self.made_by(Ansage::Valat, history)?;
+        let announced_valat = [Side::Declarers, Side::Opponents]
+            .into_iter()
+            .any(|side| self.announcement(Ansage::Valat, side).is_some());
+        let ansagen = Ansage::ALL
+            .into_iter()
+            .filter(|&ansage| (ansage == Ansage::Valat) == (valat.is_some() || announced_valat));
+
+        let mut bonuses = Vec::new();
+        for ansage in ansagen {
+            let made = self.made_by(ansage, history)?;
+            let mut announced = false;
+            for side in [Side::Declarers, Side::Opponents] {
+                let Some(multiplier) = self.announcement(ansage, side) else {
+                    continue;
+                };
+                announced = true;
+                let outcome = match made {
+                    Some(maker) if maker == side => BonusOutcome::Achieved,
+                    Some(_) => BonusOutcome::AchievedByOpponents,
+                    None => BonusOutcome::Failed,
+                };
+                bonuses.push(Bonus {
+                    ansage,
+                    side,
+                    announced: true,
+                    outcome,
+                    multiplier,
+                });
+            }
+            if let (false, Some(side)) = (announced, made) {
+                bonuses.push(Bonus {
+                    ansage,
+                    side,
+                    announced: false,
+                    outcome: BonusOutcome::Achieved,
+                    multiplier: 1,
+                });
+            }
+        }
+        Some(bonuses)
+    }
+
+    /// Settlement of a finished game including its bonuses valued by `rules`, see
+    /// [`GameState::settlement`].
+    ///
+    /// If Valat is announced or made, it replaces the game and all other bonuses.
+    pub fn settlement_with_bonuses(
+        &self,
+        history: &[TakenStich],
+        rules: &Ruleset,
+    ) -> Option<[i32; NUM_PLAYERS]> {
+        let bonuses = self.bonuses(history)?;
+        let mut balance = if bonuses.iter().any(|bonus| bonus.ansage == Ansage::Valat) {
+            [0; NUM_PLAYERS]
+        } else {
+            self.settlement(rules)?
+        };
+
+        let Some(&declarer) = self.declarers().first() else {
+            return Some(balance);
+        };
+        let game_value = rules.value(self.game_type()?)?;
+        for bonus in bonuses {
+            let value = bonus.value(&rules.bonuses, game_value);
+            let amount = match bonus.side {
+                Side::Declarers => value,
+                Side::Opponents => -value,
+            };
+            self.pay_team(&mut balance, declarer, amount)?;
+        }
+        Some(balance)
+    }
+
+    /// Spritzen multiplier of the announcement of `ansage` by `side`, `None` if it was not
+    /// announced.
+    fn announcement(&self, ansage: Ansage, side: Side) -> Option<u32> {
+        (0..NUM_PLAYERS)
+            .filter(|&player| self.side(player) == Some(side))
+            .map(|player| &self.players[player].calls)
+            .filter(|calls| calls.announced(ansage))
+            .map(|calls| calls.spritzen.multiplier(SpritzenTarget::Ansage(ansage)))
+            .max()
+    }
+
+    /// Side that made the bonus, `Some(None)` if nobody did and `None` if it depends on unknown
+    /// cards.
+    fn made_by(&self, ansage: Ansage, history: &[TakenStich]) -> Option<Option<Side>> {
+        // The card that has to win the given stich, counted from the last one
+        let ultimo = |card: Card, from_last: usize| {
+            let taken = &history[HAND_SIZE - from_last];
+            if taken.stich.contains(Card::UNKNOWN) {
+                return None;
+            }
+            Some(if taken.winning_card() == Some(card) {
+                self.side(taken.winner)
+            } else {
+                None
+            })
+        };
+        // The side whose stiche hold all of `cards`
+        let collected = |cards: CardSet| {
+            let mut stiche = [CardSet::new(); 2];
+            for player in 0..NUM_PLAYERS {
+                let index = (self.side(player)? == Side::Opponents) as usize;
+                stiche[index] = stiche[index].union(&self.players[player].stiche.cards);
+            }
+            if stiche[0].union(&stiche[1]).unknown_len() > 0 {
+                return None;
+            }
+            Some(if cards.is_subset(&stiche[0]) {
+                Some(Side::Declarers)
+            } else if cards.is_subset(&stiche[1]) {
+                Some(Side::Opponents)
+            } else {
+                None
+            })
+        };
+
+        match ansage {
+            Ansage::Pagat => ultimo(Card::T1, 1),
+            Ansage::Uhu => ultimo(Card::T2, 2),
+            Ansage::Pelikan => ultimo(Card::T3, 3),
+            Ansage::Quapil => ultimo(Card::T4, 4),
+            Ansage::Trull => collected([Card::T1, Card::T21, Card::T22].into_iter().collect()),
+            Ansage::Kings => collected(
+                [Card::HK, Card::PK, Card::KK, Card::XK]
+                    .into_iter()
+                    .collect(),
+            ),
+            Ansage::Ultimo => {
+                let called_king = self.players[*self.declarers().first()?].calls.called_king;
+                match called_king {
+                    Some(king) => ultimo(king, 1),
+                    None => Some(None),
+                }
+            }
+            Ansage::Valat => {
+                let side = self.side(history[0].winner)?;
+                Some(
+                    history
+                        .iter()
+                        .all(|taken| self.side(taken.winner) == Some(side))
+                        .then_some(side),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::game_type::GameType;
+    use crate::model::stich::Stich;
+
+    // Player 1 made all colour cards in a Rufer and played the called king themselves, the other
+    // players split the Tarock
+    const RUFER: &str = "/#/HKHDHPHBH1H2H3H4PKPDPPPBP10P9P8P7KKKDKPKBK1K2K3K4XKXDXPXBX10X9X8X7#/T1T2T3T4T5T6T7T8#/T9T10T11T12T13T14T15T16#/T17T18T19T20T21T22# R1XK11 2 /// - -";
+
+    /// Stiche led by player 1 and won by `winners`, the last one led with `last`.
+    fn history(winners: [usize; HAND_SIZE], last: [Card; 4]) -> Vec<TakenStich> {
+        winners
+            .iter()
+            .enumerate()
+            .map(|(index, &winner)| TakenStich {
+                stich: if index == HAND_SIZE - 1 {
+                    Stich::from_iter(last)
+                } else {
+                    Stich::from_iter([Card::H4, Card::H3, Card::H2, Card::H1])
+                },
+                leader: 0,
+                winner,
+            })
+            .collect()
+    }
+
+    fn bonus(ansage: Ansage, side: Side, announced: bool, outcome: BonusOutcome) -> Bonus {
+        Bonus {
+            ansage,
+            side,
+            announced,
+            outcome,
+            multiplier: 1,
+        }
+    }
+
+    #[test]
+    fn silent_bonuses() {
+        let game_state = GameState::from_str(RUFER).unwrap();
+        // Player 2 wins the last stich with the Pagat
+        let history = history(
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            [Card::H4, Card::T1, Card::H3, Card::H2],
+        );
+        assert_eq!(
+            game_state.bonuses(&history),
+            Some(vec![
+                bonus(
+                    Ansage::Pagat,
+                    Side::Opponents,
+                    false,
+                    BonusOutcome::Achieved
+                ),
+                bonus(
+                    Ansage::Trull,
+                    Side::Opponents,
+                    false,
+                    BonusOutcome::Achieved
+                ),
+                bonus(
+                    Ansage::Kings,
+                    Side::Declarers,
+                    false,
+                    BonusOutcome::Achieved
+                ),
+            ])
+        );
+        // The game (+3 for player 1) plus Kings (+3) minus Pagat and Trull (-3 each)
+        assert_eq!(
+            game_state.settlement_with_bonuses(&history, &Ruleset::default()),
+            Some([0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn announced_bonuses() {
+        let game_state =
+            GameState::from_str(&RUFER.replace("2 /// - -", "2 1U/T// - U12")).unwrap();
+        // The called Kreuz König wins the last stich for the opponents
+        let history = history(
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3],
+            [Card::X7, Card::X8, Card::X9, Card::XK],
+        );
+        assert_eq!(
+            game_state.bonuses(&history),
+            Some(vec![
+                bonus(Ansage::Pagat, Side::Declarers, true, BonusOutcome::Failed),
+                bonus(Ansage::Trull, Side::Opponents, true, BonusOutcome::Achieved),
+                Bonus {
+                    multiplier: 2,
+                    ..bonus(
+                        Ansage::Ultimo,
+                        Side::Declarers,
+                        true,
+                        BonusOutcome::AchievedByOpponents
+                    )
+                },
+                bonus(
+                    Ansage::Kings,
+                    Side::Declarers,
+                    false,
+                    BonusOutcome::Achieved
+                ),
+            ])
+        );
+        let rules = Ruleset::default();
+        assert_eq!(
+            game_state.bonuses(&history).unwrap()[2].value(&rules.bonuses, 1),
+            -8
+        );
+        // The game (+1) and Kings (+1) against Pagat (-2), Trull (-2) and Ultimo (-8)
+        assert_eq!(
+            game_state.settlement_with_bonuses(&history, &rules),
+            Some([-30, 10, 10, 10])
+        );
+    }
+
+    #[test]
+    fn valat_overrides() {
+        let game_state = GameState::from_str(RUFER).unwrap();
+        let history = history([0; HAND_SIZE], [Card::H4, Card::T1, Card::H3, Card::H2]);
+        assert_eq!(
+            game_state.bonuses(&history),
+            Some(vec![bonus(
+                Ansage::Valat,
+                Side::Declarers,
+                false,
+                BonusOutcome::Achieved
+            )])
+        );
+        assert_eq!(
+            game_state.settlement_with_bonuses(&history, &Ruleset::default()),
+            Some([12, -4, -4, -4])
+        );
+
+        // An announced Valat that fails is the only thing that counts as well
+        let game_state = GameState::from_str(&RUFER.replace("2 ///", "2 V///")).unwrap();
+        let history = self::history(
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            [Card::H4, Card::T1, Card::H3, Card::H2],
+        );
+        assert_eq!(
+            game_state.bonuses(&history),
+            Some(vec![bonus(
+                Ansage::Valat,
+                Side::Declarers,
+                true,
+                BonusOutcome::Failed
+            )])
+        );
+        assert_eq!(
+            game_state.settlement_with_bonuses(&history, &Ruleset::default()),
+            Some([-24, 8, 8, 8])
+        );
+    }
+
+    #[test]
+    fn bonuses_need_finished_game() {
+        let mut game_state = GameState::from_str(RUFER).unwrap();
+        let history = history([0; HAND_SIZE], [Card::H4, Card::T1, Card::H3, Card::H2]);
+        assert_eq!(game_state.bonuses(&history[1..]), None);
+
+        game_state.players[0].calls.typ = Some(GameType::B);
+        assert_eq!(game_state.bonuses(&history), Some(Vec::new()));
+
+        game_state.players[1].hand.cards.insert(Card::UNKNOWN);
+        assert_eq!(game_state.bonuses(&history), None);
+    }
+}
@@ -1,5 +1,10 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
 use std::str::FromStr;
 
+use super::error::{TafError, TafLocation};
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Card {
     UNKNOWN,
@@ -59,8 +64,217 @@ pub enum Card {
     T22,
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Suit {
+    Herz,
+    Pick,
+    Karo,
+    Kreuz,
+    Tarock,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 5] = [
+        Suit::Herz,
+        Suit::Pick,
+        Suit::Karo,
+        Suit::Kreuz,
+        Suit::Tarock,
+    ];
+
+    pub fn is_red(self) -> bool {
+        matches!(self, Suit::Herz | Suit::Karo)
+    }
+
+    /// All cards of the suit in deck order, i.e. Farben from König down and Tarock from Pagat up.
+    pub fn cards(self) -> impl Iterator<Item = Card> {
+        let range = match self {
+            Suit::Tarock => SUITS.len() * SUIT_RANKS.len()..NUM_CARDS,
+            suit => {
+                let start = suit as usize * SUIT_RANKS.len();
+                start..start + SUIT_RANKS.len()
+            }
+        };
+        Card::ALL[range].iter().copied()
+    }
+}
+
+/// Rank of a card within its suit, ordered from lowest to highest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Rank {
+    /// Leere Karte, from 1 (H4, K4, P7, X7) up to 4 (H1, K1, P10, X10).
+    ///
+    /// The pip order of red suits is inverted, so the 1 is their highest Leere.
+    Leer(u8),
+    Bube,
+    Kavall,
+    Dame,
+    König,
+    /// Tarock with its trump number, from 1 (Pagat) up to 22 (Sküs).
+    Tarock(u8),
+}
+
+impl Rank {
+    /// Zählwert of a card of this rank.
+    pub fn points(self) -> u8 {
+        match self {
+            Rank::König => 5,
+            Rank::Dame => 4,
+            Rank::Kavall => 3,
+            Rank::Bube => 2,
+            Rank::Tarock(1 | 21 | 22) => 5,
+            Rank::Leer(_) | Rank::Tarock(_) => 1,
+        }
+    }
+}
+
+pub const NUM_CARDS: usize = 54;
+
+const SUITS: [Suit; 4] = [Suit::Herz, Suit::Pick, Suit::Karo, Suit::Kreuz];
+const SUIT_RANKS: [Rank; 8] = [
+    Rank::König,
+    Rank::Dame,
+    Rank::Kavall,
+    Rank::Bube,
+    Rank::Leer(4),
+    Rank::Leer(3),
+    Rank::Leer(2),
+    Rank::Leer(1),
+];
+
+impl Card {
+    /// The full deck in declaration order. Does not contain `UNKNOWN`.
+    pub const ALL: [Card; NUM_CARDS] = [
+        Card::HK,
+        Card::HD,
+        Card::HP,
+        Card::HB,
+        Card::H1,
+        Card::H2,
+        Card::H3,
+        Card::H4,
+        Card::PK,
+        Card::PD,
+        Card::PP,
+        Card::PB,
+        Card::P10,
+        Card::P9,
+        Card::P8,
+        Card::P7,
+        Card::KK,
+        Card::KD,
+        Card::KP,
+        Card::KB,
+        Card::K1,
+        Card::K2,
+        Card::K3,
+        Card::K4,
+        Card::XK,
+        Card::XD,
+        Card::XP,
+        Card::XB,
+        Card::X10,
+        Card::X9,
+        Card::X8,
+        Card::X7,
+        Card::T1,
+        Card::T2,
+        Card::T3,
+        Card::T4,
+        Card::T5,
+        Card::T6,
+        Card::T7,
+        Card::T8,
+        Card::T9,
+        Card::T10,
+        Card::T11,
+        Card::T12,
+        Card::T13,
+        Card::T14,
+        Card::T15,
+        Card::T16,
+        Card::T17,
+        Card::T18,
+        Card::T19,
+        Card::T20,
+        Card::T21,
+        Card::T22,
+    ];
+
+    /// Dense index of the card in [`Card::ALL`], from 0 to 53.
+    ///
+    /// # Panics
+    ///
+    /// Panics for `UNKNOWN`, which is not part of the deck.
+    pub fn index(self) -> usize {
+        match self {
+            Card::UNKNOWN => panic!("UNKNOWN card has no index"),
+            card => card as usize - 1,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Option<Card> {
+        Card::ALL.get(index).copied()
+    }
+
+    /// Suit of the card, `None` for `UNKNOWN`.
+    pub fn suit(self) -> Option<Suit> {
+        match self as usize {
+            0 => None,
+            n @ 1..=32 => Some(SUITS[(n - 1) / SUIT_RANKS.len()]),
+            _ => Some(Suit::Tarock),
+        }
+    }
+
+    /// Rank of the card within its suit, `None` for `UNKNOWN`.
+    pub fn rank(self) -> Option<Rank> {
+        match self as usize {
+            0 => None,
+            n @ 1..=32 => Some(SUIT_RANKS[(n - 1) % SUIT_RANKS.len()]),
+            n => Some(Rank::Tarock((n - 32) as u8)),
+        }
+    }
+
+    /// Zählwert of the card, `None` for `UNKNOWN`.
+    pub fn points(self) -> Option<u8> {
+        self.rank().map(Rank::points)
+    }
+
+    /// Number of a Tarock, `None` for all other cards.
+    pub fn trump_number(self) -> Option<u8> {
+        match self.rank() {
+            Some(Rank::Tarock(number)) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn is_tarock(self) -> bool {
+        self.suit() == Some(Suit::Tarock)
+    }
+
+    /// One of the Trull: Pagat, Mond or Sküs.
+    pub fn is_trullstück(self) -> bool {
+        matches!(self, Card::T1 | Card::T21 | Card::T22)
+    }
+}
+
+/// Orders cards by suit and then by rank within the suit, `UNKNOWN` first.
+///
+/// Tarock are ordered above all other suits.
+impl Ord for Card {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.suit(), self.rank()).cmp(&(other.suit(), other.rank()))
+    }
+}
+
+impl PartialOrd for Card {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FromStr for Card {
-    type Err = &'static str;
+    type Err = TafError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_uppercase().as_str() {
@@ -119,7 +333,18 @@ impl FromStr for Card {
             "T20" => Ok(Card::T20),
             "T21" => Ok(Card::T21),
             "T22" => Ok(Card::T22),
-            _ => Err("Unknown card"),
+            _ => Err(TafError::InvalidCard {
+                location: TafLocation::new(s, 0..s.len()),
+            }),
+        }
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Card::UNKNOWN => write!(f, "."),
+            card => write!(f, "{card:?}"),
         }
     }
 }
@@ -127,6 +352,193 @@ impl FromStr for Card {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::strategies::arb_card;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    proptest! {
+        #[test]
+        fn card_display_round_trip(card in arb_card()) {
+            prop_assert_eq!(Card::from_str(&card.to_string()), Ok(card));
+        }
+    }
+
+    #[test]
+    fn card_all() {
+        assert_eq!(Card::ALL.len(), 54);
+        assert!(!Card::ALL.contains(&Card::UNKNOWN));
+        assert_eq!(Card::ALL.iter().collect::<HashSet<_>>().len(), 54);
+    }
+
+    #[test]
+    fn card_index() {
+        for (index, card) in Card::ALL.iter().enumerate() {
+            assert_eq!(card.index(), index);
+            assert_eq!(Card::from_index(index), Some(*card));
+        }
+        assert_eq!(Card::HK.index(), 0);
+        assert_eq!(Card::T22.index(), 53);
+        assert_eq!(Card::from_index(54), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn card_index_unknown() {
+        Card::UNKNOWN.index();
+    }
+
+    #[test]
+    fn suit_cards() {
+        for suit in Suit::ALL {
+            assert!(suit.cards().all(|card| card.suit() == Some(suit)));
+        }
+        assert_eq!(
+            Suit::ALL
+                .iter()
+                .map(|suit| suit.cards().count())
+                .sum::<usize>(),
+            54
+        );
+        assert_eq!(Suit::Tarock.cards().count(), 22);
+        assert_eq!(
+            Suit::Karo.cards().collect::<Vec<_>>(),
+            vec![
+                Card::KK,
+                Card::KD,
+                Card::KP,
+                Card::KB,
+                Card::K1,
+                Card::K2,
+                Card::K3,
+                Card::K4
+            ]
+        );
+        assert!(Suit::Kreuz.cards().is_sorted_by(|a, b| a > b));
+        assert!(Suit::Tarock.cards().is_sorted_by(|a, b| a < b));
+    }
+
+    #[test]
+    fn card_suit() {
+        assert_eq!(Card::UNKNOWN.suit(), None);
+        assert_eq!(Card::HK.suit(), Some(Suit::Herz));
+        assert_eq!(Card::H4.suit(), Some(Suit::Herz));
+        assert_eq!(Card::PK.suit(), Some(Suit::Pick));
+        assert_eq!(Card::P7.suit(), Some(Suit::Pick));
+        assert_eq!(Card::KK.suit(), Some(Suit::Karo));
+        assert_eq!(Card::K4.suit(), Some(Suit::Karo));
+        assert_eq!(Card::XK.suit(), Some(Suit::Kreuz));
+        assert_eq!(Card::X7.suit(), Some(Suit::Kreuz));
+        assert_eq!(Card::T1.suit(), Some(Suit::Tarock));
+        assert_eq!(Card::T22.suit(), Some(Suit::Tarock));
+
+        assert!(Suit::Herz.is_red());
+        assert!(Suit::Karo.is_red());
+        assert!(!Suit::Pick.is_red());
+        assert!(!Suit::Kreuz.is_red());
+        assert!(!Suit::Tarock.is_red());
+    }
+
+    #[test]
+    fn card_rank() {
+        assert_eq!(Card::UNKNOWN.rank(), None);
+        assert_eq!(Card::HK.rank(), Some(Rank::König));
+        assert_eq!(Card::KD.rank(), Some(Rank::Dame));
+        assert_eq!(Card::PP.rank(), Some(Rank::Kavall));
+        assert_eq!(Card::XB.rank(), Some(Rank::Bube));
+        assert_eq!(Card::H1.rank(), Some(Rank::Leer(4)));
+        assert_eq!(Card::H4.rank(), Some(Rank::Leer(1)));
+        assert_eq!(Card::K2.rank(), Some(Rank::Leer(3)));
+        assert_eq!(Card::P10.rank(), Some(Rank::Leer(4)));
+        assert_eq!(Card::X7.rank(), Some(Rank::Leer(1)));
+        assert_eq!(Card::X9.rank(), Some(Rank::Leer(3)));
+        assert_eq!(Card::T1.rank(), Some(Rank::Tarock(1)));
+        assert_eq!(Card::T15.rank(), Some(Rank::Tarock(15)));
+        assert_eq!(Card::T22.rank(), Some(Rank::Tarock(22)));
+    }
+
+    #[test]
+    fn card_points() {
+        assert_eq!(Card::UNKNOWN.points(), None);
+        assert_eq!(Card::HK.points(), Some(5));
+        assert_eq!(Card::PD.points(), Some(4));
+        assert_eq!(Card::KP.points(), Some(3));
+        assert_eq!(Card::XB.points(), Some(2));
+        assert_eq!(Card::H1.points(), Some(1));
+        assert_eq!(Card::P10.points(), Some(1));
+        assert_eq!(Card::T1.points(), Some(5));
+        assert_eq!(Card::T2.points(), Some(1));
+        assert_eq!(Card::T20.points(), Some(1));
+        assert_eq!(Card::T21.points(), Some(5));
+        assert_eq!(Card::T22.points(), Some(5));
+
+        assert!(Card::T1.is_trullstück());
+        assert!(Card::T21.is_trullstück());
+        assert!(Card::T22.is_trullstück());
+        assert!(!Card::T20.is_trullstück());
+        assert!(!Card::HK.is_trullstück());
+    }
+
+    #[test]
+    fn card_trump_number() {
+        assert_eq!(Card::UNKNOWN.trump_number(), None);
+        assert_eq!(Card::HK.trump_number(), None);
+        assert_eq!(Card::X7.trump_number(), None);
+        assert_eq!(Card::T1.trump_number(), Some(1));
+        assert_eq!(Card::T10.trump_number(), Some(10));
+        assert_eq!(Card::T22.trump_number(), Some(22));
+
+        assert!(Card::T1.is_tarock());
+        assert!(!Card::HK.is_tarock());
+        assert!(!Card::UNKNOWN.is_tarock());
+    }
+
+    #[test]
+    fn card_ord() {
+        assert!(Card::H1 > Card::H2);
+        assert!(Card::H4 < Card::H3);
+        assert!(Card::K1 > Card::K4);
+        assert!(Card::P10 > Card::P9);
+        assert!(Card::X7 < Card::X8);
+        assert!(Card::HB > Card::H1);
+        assert!(Card::XB > Card::X10);
+        assert!(Card::HK > Card::HD);
+        assert!(Card::HD > Card::HP);
+        assert!(Card::HP > Card::HB);
+        assert!(Card::T1 > Card::HK);
+        assert!(Card::T1 > Card::XK);
+        assert!(Card::T22 > Card::T21);
+        assert!(Card::T2 > Card::T1);
+        assert!(Card::UNKNOWN < Card::H4);
+
+        let mut cards = vec![
+            Card::T1,
+            Card::H1,
+            Card::T22,
+            Card::H4,
+            Card::HK,
+            Card::UNKNOWN,
+        ];
+        cards.sort();
+        assert_eq!(
+            cards,
+            vec![
+                Card::UNKNOWN,
+                Card::H4,
+                Card::H1,
+                Card::HK,
+                Card::T1,
+                Card::T22
+            ]
+        );
+    }
+
+    #[test]
+    fn card_display() {
+        assert_eq!(Card::UNKNOWN.to_string(), ".");
+        assert_eq!(Card::HK.to_string(), "HK");
+        assert_eq!(Card::P10.to_string(), "P10");
+        assert_eq!(Card::T22.to_string(), "T22");
+    }
 
     #[test]
     fn card_from_str() {
@@ -186,11 +598,29 @@ mod tests {
         assert_eq!(Card::from_str("T21"), Ok(Card::T21));
         assert_eq!(Card::from_str("T22"), Ok(Card::T22));
 
-        assert_eq!(Card::from_str("T23"), Err("Unknown card"));
-        assert_eq!(Card::from_str("asd"), Err("Unknown card"));
-        assert_eq!(Card::from_str("4564"), Err("Unknown card"));
-        assert_eq!(Card::from_str("X1"), Err("Unknown card"));
-        assert_eq!(Card::from_str("X4"), Err("Unknown card"));
-        assert_eq!(Card::from_str("H10"), Err("Unknown card"));
+        assert!(matches!(
+            Card::from_str("T23"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            Card::from_str("asd"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            Card::from_str("4564"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            Card::from_str("X1"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            Card::from_str("X4"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            Card::from_str("H10"),
+            Err(TafError::InvalidCard { .. })
+        ));
     }
 }
@@ -0,0 +1,311 @@
+use std::fmt::{Debug, Display};
+
+use super::card::{Card, Suit, NUM_CARDS};
+
+const ALL_BITS: u64 = (1 << NUM_CARDS) - 1;
+
+/// Set of cards backed by a bitset over [`Card::index`].
+///
+/// `UNKNOWN` cards cannot be told apart, so they are only counted.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CardSet {
+    bits: u64,
+    unknown: u8,
+}
+
+impl CardSet {
+    pub const fn new() -> Self {
+        Self {
+            bits: 0,
+            unknown: 0,
+        }
+    }
+
+    /// The full deck without any unknown cards.
+    pub const fn full() -> Self {
+        Self {
+            bits: ALL_BITS,
+            unknown: 0,
+        }
+    }
+
+    /// All cards of `suit`.
+    pub fn of_suit(suit: Suit) -> Self {
+        suit.cards().collect()
+    }
+
+    /// Inserts a card, returns `false` if a known card was already present.
+    pub fn insert(&mut self, card: Card) -> bool {
+        match card {
+            Card::UNKNOWN => {
+                self.unknown += 1;
+                true
+            }
+            card => {
+                let present = self.contains(card);
+                self.bits |= 1 << card.index();
+                !present
+            }
+        }
+    }
+
+    /// Removes a card, returns `false` if it was not present.
+    pub fn remove(&mut self, card: Card) -> bool {
+        match card {
+            Card::UNKNOWN if self.unknown > 0 => {
+                self.unknown -= 1;
+                true
+            }
+            Card::UNKNOWN => false,
+            card => {
+                let present = self.contains(card);
+                self.bits &= !(1 << card.index());
+                present
+            }
+        }
+    }
+
+    /// Whether the set contains `card`. For `UNKNOWN` whether it contains any unknown card.
+    pub fn contains(&self, card: Card) -> bool {
+        match card {
+            Card::UNKNOWN => self.unknown > 0,
+            card => self.bits & (1 << card.index()) != 0,
+        }
+    }
+
+    /// Known and unknown cards of both sets.
+    pub fn union(&self, other: &CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits | other.bits,
+            unknown: self.unknown + other.unknown,
+        }
+    }
+
+    /// Known cards contained in both sets.
+    pub fn intersection(&self, other: &CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits & other.bits,
+            unknown: 0,
+        }
+    }
+
+    /// Known cards of this set that are not in `other`, plus the unknown cards of this set.
+    pub fn difference(&self, other: &CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits & !other.bits,
+            unknown: self.unknown,
+        }
+    }
+
+    /// Only the known cards of this set.
+    pub fn known(&self) -> CardSet {
+        CardSet {
+            bits: self.bits,
+            unknown: 0,
+        }
+    }
+
+    /// Known cards that are not in this set.
+    pub fn complement(&self) -> CardSet {
+        CardSet {
+            bits: !self.bits & ALL_BITS,
+            unknown: 0,
+        }
+    }
+
+    /// Number of known and unknown cards.
+    pub fn len(&self) -> usize {
+        self.known_len() + self.unknown_len()
+    }
+
+    pub fn known_len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn unknown_len(&self) -> usize {
+        self.unknown as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the set shares no known card with `other`.
+    pub fn is_disjoint(&self, other: &CardSet) -> bool {
+        self.bits & other.bits == 0
+    }
+
+    /// Whether every known card of this set is in `other` and `other` has at least as many unknown
+    /// cards.
+    pub fn is_subset(&self, other: &CardSet) -> bool {
+        self.bits & !other.bits == 0 && self.unknown <= other.unknown
+    }
+
+    /// Iterates over the known cards in index order. Unknown cards are skipped.
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter { bits: self.bits }
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut card_set = CardSet::new();
+        card_set.extend(iter);
+        card_set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<T: IntoIterator<Item = Card>>(&mut self, iter: T) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct CardSetIter {
+    bits: u64,
+}
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+        let index = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        Card::from_index(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+/// Writes the known cards in index order followed by the unknown cards.
+impl Display for CardSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for card in self.iter() {
+            write!(f, "{card}")?;
+        }
+        for _ in 0..self.unknown {
+            write!(f, "{}", Card::UNKNOWN)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for CardSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CardSet({self})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_set_insert_remove() {
+        let mut card_set = CardSet::new();
+        assert!(card_set.is_empty());
+        assert!(!card_set.contains(Card::HK));
+        assert!(!card_set.contains(Card::UNKNOWN));
+
+        assert!(card_set.insert(Card::HK));
+        assert!(!card_set.insert(Card::HK));
+        assert!(card_set.insert(Card::T22));
+        assert!(card_set.insert(Card::UNKNOWN));
+        assert!(card_set.insert(Card::UNKNOWN));
+        assert!(card_set.contains(Card::HK));
+        assert!(card_set.contains(Card::T22));
+        assert!(card_set.contains(Card::UNKNOWN));
+        assert!(!card_set.contains(Card::T21));
+        assert_eq!(card_set.len(), 4);
+        assert_eq!(card_set.known_len(), 2);
+        assert_eq!(card_set.unknown_len(), 2);
+
+        assert!(card_set.remove(Card::HK));
+        assert!(!card_set.remove(Card::HK));
+        assert!(card_set.remove(Card::UNKNOWN));
+        assert!(card_set.remove(Card::UNKNOWN));
+        assert!(!card_set.remove(Card::UNKNOWN));
+        assert_eq!(card_set, CardSet::from_iter([Card::T22]));
+    }
+
+    #[test]
+    fn card_set_operations() {
+        let a = CardSet::from_iter([Card::HK, Card::HD, Card::T1, Card::UNKNOWN]);
+        let b = CardSet::from_iter([Card::HD, Card::T1, Card::T2, Card::UNKNOWN]);
+
+        assert_eq!(
+            a.union(&b),
+            CardSet::from_iter([
+                Card::HK,
+                Card::HD,
+                Card::T1,
+                Card::T2,
+                Card::UNKNOWN,
+                Card::UNKNOWN
+            ])
+        );
+        assert_eq!(a.intersection(&b), CardSet::from_iter([Card::HD, Card::T1]));
+        assert_eq!(
+            a.difference(&b),
+            CardSet::from_iter([Card::HK, Card::UNKNOWN])
+        );
+        assert_eq!(
+            a.known(),
+            CardSet::from_iter([Card::HK, Card::HD, Card::T1])
+        );
+        assert!(!a.is_disjoint(&b));
+        assert!(a.is_disjoint(&CardSet::from_iter([Card::T22])));
+        assert!(a.known().is_subset(&a));
+        assert!(a.is_subset(&a.union(&b)));
+        assert!(!a.is_subset(&b));
+        assert!(!a.is_subset(&a.known()));
+
+        assert_eq!(CardSet::full().len(), 54);
+        assert_eq!(a.complement().len(), 51);
+        assert!(a.complement().is_disjoint(&a));
+        assert_eq!(a.complement().union(&a).known_len(), 54);
+        assert_eq!(CardSet::of_suit(Suit::Tarock).len(), 22);
+        assert_eq!(CardSet::of_suit(Suit::Herz).len(), 8);
+    }
+
+    #[test]
+    fn card_set_iter() {
+        let card_set = CardSet::from_iter([Card::T22, Card::UNKNOWN, Card::HK, Card::X7]);
+        assert_eq!(card_set.iter().len(), 3);
+        assert_eq!(
+            card_set.iter().collect::<Vec<_>>(),
+            vec![Card::HK, Card::X7, Card::T22]
+        );
+        assert_eq!(
+            CardSet::full().iter().collect::<Vec<_>>(),
+            Card::ALL.to_vec()
+        );
+    }
+
+    #[test]
+    fn card_set_display() {
+        let card_set = CardSet::from_iter([Card::T22, Card::UNKNOWN, Card::HK, Card::X7]);
+        assert_eq!(card_set.to_string(), "HKX7T22.");
+        assert_eq!(format!("{card_set:?}"), "CardSet(HKX7T22.)");
+        assert_eq!(CardSet::new().to_string(), "");
+    }
+}
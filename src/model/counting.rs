@@ -0,0 +1,246 @@
+//! Counting of card points in stiche.
+//!
+//! Cards are counted in groups of three: every group is worth the Zählwerte of its cards minus 2.
+//! Cards left over after grouping are counted as a fraction of a point, so each card effectively
+//! contributes its Zählwert minus ⅔. A third of a point is called a Blatt. All 54 cards together
+//! are worth exactly 70 points.
+
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::Add;
+
+use super::card_set::CardSet;
+use super::game::{GameState, NUM_PLAYERS};
+use super::game_type::TalonOwner;
+use super::talon::TalonChoice;
+
+/// Number of Blatt in a point.
+pub const BLATT_PER_POINT: u16 = 3;
+
+/// Card points, counted exactly in Blatt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Points {
+    blatt: u16,
+}
+
+impl Points {
+    /// Points of all cards of the game.
+    pub const TOTAL: Points = Points::new(70, 0);
+    /// Points a side needs to win a positive game, 35 points and 2 Blatt.
+    pub const WINNING: Points = Points::new(35, 2);
+
+    pub const fn new(points: u16, blatt: u16) -> Self {
+        Self {
+            blatt: points * BLATT_PER_POINT + blatt,
+        }
+    }
+
+    /// Counts the points of `cards`, `None` if any of them is unknown.
+    pub fn of(cards: &CardSet) -> Option<Self> {
+        if cards.unknown_len() > 0 {
+            return None;
+        }
+        // Every card is worth its Zählwert minus 2 Blatt, which is the same as the sum of every
+        // group of three minus 2 points.
+        let blatt = cards
+            .iter()
+            .map(|card| {
+                card.points()
+                    .map(|points| points as u16 * BLATT_PER_POINT - 2)
+            })
+            .sum::<Option<u16>>()?;
+        Some(Self { blatt })
+    }
+
+    /// Whole points.
+    pub const fn points(&self) -> u16 {
+        self.blatt / BLATT_PER_POINT
+    }
+
+    /// Blatt in addition to the whole points, from 0 to 2.
+    pub const fn blatt(&self) -> u16 {
+        self.blatt % BLATT_PER_POINT
+    }
+
+    /// Whether these points win a positive game.
+    pub fn wins(&self) -> bool {
+        *self >= Self::WINNING
+    }
+}
+
+impl Add for Points {
+    type Output = Points;
+
+    fn add(self, rhs: Points) -> Points {
+        Points {
+            blatt: self.blatt + rhs.blatt,
+        }
+    }
+}
+
+impl Sum for Points {
+    fn sum<I: Iterator<Item = Points>>(iter: I) -> Self {
+        iter.fold(Points::default(), Add::add)
+    }
+}
+
+impl Display for Points {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.blatt() {
+            0 => write!(f, "{}", self.points()),
+            blatt => write!(f, "{} {blatt}/{BLATT_PER_POINT}", self.points()),
+        }
+    }
+}
+
+impl GameState {
+    /// Points in the stiche of `player`, `None` if any of them is unknown.
+    pub fn stiche_points(&self, player: usize) -> Option<Points> {
+        Points::of(&self.players[player].stiche.cards)
+    }
+
+    /// Points of the team made up of `players`, `None` if any of the counted cards is unknown.
+    ///
+    /// Besides the stiche of the players, the cards left in the talon are counted for the team if
+    /// they belong to its side, see [`GameType::talon_owner`](super::game_type::GameType::talon_owner).
+    /// A talon that goes to the opponents untouched always belongs to them. Talon cards the
+    /// declarer laid down or handed over are counted with the stiche they lie on.
+    pub fn team_points(&self, players: &[usize]) -> Option<Points> {
+        let mut cards = CardSet::new();
+        for &player in players {
+            cards = cards.union(&self.players[player].stiche.cards);
+        }
+
+        let has_declarer = players
+            .iter()
+            .any(|&player| self.players[player].calls.typ.is_some());
+        let owner = self.players.iter().find_map(|player| {
+            let calls = &player.calls;
+            calls.typ.map(|typ| match calls.taken_talon {
+                TalonChoice::ToOpponents => TalonOwner::Opponents,
+                _ => typ.talon_owner(),
+            })
+        });
+        let owns_talon = match owner {
+            Some(TalonOwner::Declarer) => has_declarer,
+            Some(TalonOwner::Opponents) => !has_declarer,
+            Some(TalonOwner::Nobody) | None => false,
+        };
+        if owns_talon {
+            cards = cards
+                .union(&self.talon[0].cards)
+                .union(&self.talon[1].cards);
+        }
+        Points::of(&cards)
+    }
+
+    /// Players opposing the declarers, without regard to any partner.
+    pub fn opponents(&self) -> Vec<usize> {
+        (0..NUM_PLAYERS)
+            .filter(|&player| self.players[player].calls.typ.is_none())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::card::Card;
+    use crate::model::game_type::GameType;
+
+    // Player 1 played a Rufer, laid down HKX10X9 and left the lower talon half to the opponents
+    const COUNTED: &str = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HKX10X9#.........../#.........../#.........../HDH1HBT22# R1XK-1 4 /// - -";
+
+    #[test]
+    fn points_of_cards() {
+        assert_eq!(Points::of(&CardSet::new()), Some(Points::default()));
+        assert_eq!(Points::of(&CardSet::full()), Some(Points::TOTAL));
+        assert_eq!(
+            Points::of(&[Card::HK, Card::HD, Card::T1].into_iter().collect()),
+            Some(Points::new(12, 0))
+        );
+        assert_eq!(
+            Points::of(&[Card::HK].into_iter().collect()),
+            Some(Points::new(4, 1))
+        );
+        assert_eq!(
+            Points::of(&[Card::H4, Card::X7].into_iter().collect()),
+            Some(Points::new(0, 2))
+        );
+        assert_eq!(
+            Points::of(&[Card::HK, Card::UNKNOWN].into_iter().collect()),
+            None
+        );
+    }
+
+    #[test]
+    fn points_display_and_winning() {
+        assert_eq!(Points::new(35, 2).to_string(), "35 2/3");
+        assert_eq!(Points::new(70, 0).to_string(), "70");
+        assert_eq!(Points::new(12, 4), Points::new(13, 1));
+        assert!(Points::new(35, 2).wins());
+        assert!(!Points::new(35, 1).wins());
+        assert_eq!(
+            [Points::new(1, 2), Points::new(2, 2)]
+                .into_iter()
+                .sum::<Points>(),
+            Points::new(4, 1)
+        );
+    }
+
+    #[test]
+    fn stiche_points() {
+        let game_state = GameState::from_str(COUNTED).unwrap();
+        assert_eq!(game_state.stiche_points(0), Some(Points::new(5, 0)));
+        assert_eq!(game_state.stiche_points(1), Some(Points::default()));
+        assert_eq!(game_state.stiche_points(3), Some(Points::new(9, 1)));
+    }
+
+    #[test]
+    fn team_points_with_talon() {
+        let mut game_state = GameState::from_str(COUNTED).unwrap();
+        assert_eq!(game_state.opponents(), vec![1, 2, 3]);
+
+        // The remaining talon half counts for the opponents of a Rufer
+        assert_eq!(game_state.team_points(&[0]), Some(Points::new(5, 0)));
+        assert_eq!(
+            game_state.team_points(&game_state.opponents()),
+            Some(Points::new(10, 1))
+        );
+
+        // In a Dreier it counts for the declarer
+        game_state.players[0].calls.typ = Some(GameType::D);
+        assert_eq!(game_state.team_points(&[0]), Some(Points::new(6, 0)));
+        assert_eq!(
+            game_state.team_points(&game_state.opponents()),
+            Some(Points::new(9, 1))
+        );
+
+        // In negative games nobody gets it
+        game_state.players[0].calls.typ = Some(GameType::B);
+        assert_eq!(game_state.team_points(&[0]), Some(Points::new(5, 0)));
+        assert_eq!(
+            game_state.team_points(&game_state.opponents()),
+            Some(Points::new(9, 1))
+        );
+
+        // A Solo leaves the whole talon to the opponents, also once they took it
+        game_state.players[0].calls.typ = Some(GameType::SR);
+        game_state.players[0].calls.taken_talon = TalonChoice::ToOpponents;
+        assert_eq!(game_state.team_points(&[0]), Some(Points::new(5, 0)));
+        assert_eq!(
+            game_state.team_points(&game_state.opponents()),
+            Some(Points::new(10, 1))
+        );
+        let mut handed_over = game_state;
+        let talon = std::mem::take(&mut handed_over.talon[1].cards);
+        handed_over.players[1].stiche.cards.extend(talon.iter());
+        assert_eq!(handed_over.team_points(&[0]), Some(Points::new(5, 0)));
+        assert_eq!(
+            handed_over.team_points(&handed_over.opponents()),
+            Some(Points::new(10, 1))
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::Range;
+
+use super::moves::Move;
+use super::pile::Pile;
+
+/// Part of a TAF string an error occurred in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TafGroup {
+    Talon(usize),
+    Hand,
+    Stiche,
+    Stich,
+    Cards,
+    Game,
+    LastStich,
+    Calls,
+    KleinenStechenGroßen,
+    Spritzen,
+}
+
+impl Display for TafGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TafGroup::Talon(0) => write!(f, "upper talon"),
+            TafGroup::Talon(_) => write!(f, "lower talon"),
+            TafGroup::Hand => write!(f, "hand"),
+            TafGroup::Stiche => write!(f, "stiche"),
+            TafGroup::Stich => write!(f, "current stich"),
+            TafGroup::Cards => write!(f, "cards group"),
+            TafGroup::Game => write!(f, "game group"),
+            TafGroup::LastStich => write!(f, "last stich group"),
+            TafGroup::Calls => write!(f, "calls group"),
+            TafGroup::KleinenStechenGroßen => write!(f, "kleinen stechen die großen group"),
+            TafGroup::Spritzen => write!(f, "spritzen group"),
+        }
+    }
+}
+
+/// Where in a TAF string an error occurred.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TafLocation {
+    /// The complete string that was parsed.
+    pub input: String,
+    pub group: Option<TafGroup>,
+    /// Index of the player the offending text belongs to.
+    pub player: Option<usize>,
+    /// Byte span of the offending text in `input`.
+    pub span: Range<usize>,
+}
+
+impl TafLocation {
+    pub fn new(input: &str, span: Range<usize>) -> Self {
+        Self {
+            input: input.to_string(),
+            group: None,
+            player: None,
+            span,
+        }
+    }
+
+    /// The offending text.
+    pub fn text(&self) -> &str {
+        &self.input[self.span.clone()]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TafError {
+    /// The string does not consist of the six whitespace separated TAF groups.
+    MissingGroups {
+        location: TafLocation,
+        found: usize,
+    },
+    /// A group or part of a group does not follow the TAF syntax.
+    InvalidSyntax {
+        location: TafLocation,
+        expected: &'static str,
+    },
+    InvalidCard {
+        location: TafLocation,
+    },
+    /// A known card appears more than once in the same pile.
+    DuplicateCard {
+        location: TafLocation,
+    },
+    TooManyCards {
+        location: TafLocation,
+        max: usize,
+    },
+    InvalidGameType {
+        location: TafLocation,
+    },
+    InvalidAnsage {
+        location: TafLocation,
+    },
+    InvalidSpritze {
+        location: TafLocation,
+        reason: &'static str,
+    },
+}
+
+impl TafError {
+    pub fn location(&self) -> &TafLocation {
+        match self {
+            TafError::MissingGroups { location, .. }
+            | TafError::InvalidSyntax { location, .. }
+            | TafError::InvalidCard { location }
+            | TafError::DuplicateCard { location }
+            | TafError::TooManyCards { location, .. }
+            | TafError::InvalidGameType { location }
+            | TafError::InvalidAnsage { location }
+            | TafError::InvalidSpritze { location, .. } => location,
+        }
+    }
+
+    fn location_mut(&mut self) -> &mut TafLocation {
+        match self {
+            TafError::MissingGroups { location, .. }
+            | TafError::InvalidSyntax { location, .. }
+            | TafError::InvalidCard { location }
+            | TafError::DuplicateCard { location }
+            | TafError::TooManyCards { location, .. }
+            | TafError::InvalidGameType { location }
+            | TafError::InvalidAnsage { location }
+            | TafError::InvalidSpritze { location, .. } => location,
+        }
+    }
+
+    /// Moves an error of a parsed substring into the string it was taken from.
+    ///
+    /// `offset` is the byte offset of the substring in `input`. Group and player are only set if
+    /// the error does not already carry a more specific one.
+    pub fn within(
+        mut self,
+        input: &str,
+        offset: usize,
+        group: Option<TafGroup>,
+        player: Option<usize>,
+    ) -> Self {
+        let location = self.location_mut();
+        location.input = input.to_string();
+        location.span = location.span.start + offset..location.span.end + offset;
+        if location.group.is_none() {
+            location.group = group;
+        }
+        if location.player.is_none() {
+            location.player = player;
+        }
+        self
+    }
+}
+
+impl Display for TafError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = self.location();
+        match self {
+            TafError::MissingGroups { found, .. } => {
+                write!(f, "expected 6 TAF groups, found {found}")?
+            }
+            TafError::InvalidSyntax { expected, .. } => write!(f, "expected {expected}")?,
+            TafError::InvalidCard { .. } => write!(f, "invalid card `{}`", location.text())?,
+            TafError::DuplicateCard { .. } => write!(f, "duplicate card `{}`", location.text())?,
+            TafError::TooManyCards { max, .. } => {
+                write!(f, "too many cards, at most {max} are allowed")?
+            }
+            TafError::InvalidGameType { .. } => {
+                write!(f, "invalid game type `{}`", location.text())?
+            }
+            TafError::InvalidAnsage { .. } => write!(f, "invalid ansage `{}`", location.text())?,
+            TafError::InvalidSpritze { reason, .. } => {
+                write!(f, "invalid spritze `{}`: {reason}", location.text())?
+            }
+        }
+        match (location.group, location.player) {
+            (Some(group), Some(player)) => write!(f, " in {group} of player {}", player + 1)?,
+            (Some(group), None) => write!(f, " in {group}")?,
+            (None, Some(player)) => write!(f, " of player {}", player + 1)?,
+            (None, None) => {}
+        }
+
+        let padding = location.input[..location.span.start].chars().count();
+        let width = location.text().chars().count().max(1);
+        write!(
+            f,
+            "\n{}\n{}{}",
+            location.input,
+            " ".repeat(padding),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl Error for TafError {}
+
+/// Why two game states are not one legal move apart.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum MoveError {
+    /// Both states are the same.
+    NoMove,
+    /// The cards of these piles changed in a way no single move explains.
+    NotOneMove { changed: Vec<Pile> },
+    /// The game group, the calls or the spritzen changed.
+    GameChanged,
+    /// The cards leaving `from` are not the ones arriving in `to`.
+    CardsMismatch { from: Pile, to: Pile },
+    /// The move is not allowed in the state it is made in.
+    Illegal { mv: Move, reason: &'static str },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoMove => write!(f, "the states are the same"),
+            MoveError::NotOneMove { changed } => {
+                write!(f, "not a single move, changed piles: ")?;
+                for (index, pile) in changed.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{pile}")?;
+                }
+                Ok(())
+            }
+            MoveError::GameChanged => write!(f, "game, calls or spritzen changed"),
+            MoveError::CardsMismatch { from, to } => {
+                write!(
+                    f,
+                    "cards leaving {from} do not match the cards arriving in {to}"
+                )
+            }
+            MoveError::Illegal { mv, reason } => write!(f, "illegal move `{mv}`: {reason}"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// Error reading or writing a ROCK file.
+#[derive(Debug)]
+pub enum RockError {
+    Io(std::io::Error),
+    /// A line is not valid TAF. `line` is 1-based.
+    Taf {
+        line: usize,
+        error: TafError,
+    },
+}
+
+impl From<std::io::Error> for RockError {
+    fn from(error: std::io::Error) -> Self {
+        RockError::Io(error)
+    }
+}
+
+impl Display for RockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RockError::Io(error) => write!(f, "{error}"),
+            RockError::Taf { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl Error for RockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RockError::Io(error) => Some(error),
+            RockError::Taf { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Error reading a ruleset file.
+#[derive(Debug)]
+pub enum RulesetError {
+    Io(std::io::Error),
+    /// A line is not a valid setting. `line` is 1-based.
+    Invalid {
+        line: usize,
+        reason: &'static str,
+    },
+}
+
+impl From<std::io::Error> for RulesetError {
+    fn from(error: std::io::Error) -> Self {
+        RulesetError::Io(error)
+    }
+}
+
+impl Display for RulesetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesetError::Io(error) => write!(f, "{error}"),
+            RulesetError::Invalid { line, reason } => write!(f, "line {line}: {reason}"),
+        }
+    }
+}
+
+impl Error for RulesetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RulesetError::Io(error) => Some(error),
+            RulesetError::Invalid { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taf_error_display() {
+        let error = TafError::InvalidCard {
+            location: TafLocation::new("X1", 0..2),
+        };
+        assert_eq!(error.to_string(), "invalid card `X1`\nX1\n^^");
+
+        let error = error.within("H1X1H2", 2, Some(TafGroup::Hand), Some(1));
+        assert_eq!(
+            error.to_string(),
+            "invalid card `X1` in hand of player 2\nH1X1H2\n  ^^"
+        );
+
+        let error = TafError::MissingGroups {
+            location: TafLocation::new("a b", 3..3),
+            found: 2,
+        };
+        assert_eq!(
+            error.to_string(),
+            "expected 6 TAF groups, found 2\na b\n   ^"
+        );
+    }
+
+    #[test]
+    fn taf_error_within() {
+        let error = TafError::InvalidCard {
+            location: TafLocation {
+                input: "HKX1".to_string(),
+                group: Some(TafGroup::Stiche),
+                player: None,
+                span: 2..4,
+            },
+        }
+        .within("#HKX1", 1, Some(TafGroup::Cards), Some(3));
+
+        assert_eq!(
+            error.location(),
+            &TafLocation {
+                input: "#HKX1".to_string(),
+                group: Some(TafGroup::Stiche),
+                player: Some(3),
+                span: 3..5,
+            }
+        );
+    }
+}
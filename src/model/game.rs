@@ -1,33 +1,31 @@
-use super::card::Card;
+use super::ansage::Ansage;
+use super::bidding::Bidding;
+use super::card::{Card, NUM_CARDS};
+use super::card_set::CardSet;
+use super::error::{TafError, TafGroup, TafLocation};
 use super::game_type::GameType;
+use super::spritzen::{Spritze, Spritzen, SpritzenLevel, SpritzenTarget};
+use super::stich::{Stich, STICH_SIZE};
+use super::talon::TalonChoice;
 use regex::Regex;
-use std::collections::HashSet;
-use std::fmt::Debug;
+use std::fmt::Display;
 use std::iter;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
-const HAND_SIZE: usize = 12;
-const STICH_SIZE: usize = 4;
-const NUM_PLAYERS: usize = 4;
-const NUM_CARDS: usize = 54;
+pub const HAND_SIZE: usize = 12;
+pub const TALON_HALF_SIZE: usize = 3;
+/// Most cards a hand can hold, after the declarer took the whole talon and before laying down.
+pub const MAX_HAND_SIZE: usize = HAND_SIZE + 2 * TALON_HALF_SIZE;
+pub const NUM_PLAYERS: usize = 4;
 
 const CARD_REGEX_STR: &str = r"(?i)[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.";
 static CARD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(CARD_REGEX_STR).unwrap());
 
-const CARDS_REGEX_STR: &str = r#"(?ix)
-    ^(?<talon0>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){3})
-        /(?<talon1>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){3})
-    \#(?<player0hand>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){0,12})
-        /(?<player0stiche>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2])|1[0-9]|[1-9]|\.){0,54})
-    \#(?<player1hand>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){0,12})
-        /(?<player1stiche>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2])|1[0-9]|[1-9]|\.){0,54})
-    \#(?<player2hand>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){0,12})
-        /(?<player2stiche>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2])|1[0-9]|[1-9]|\.){0,54})
-    \#(?<player3hand>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){0,12})
-        /(?<player3stiche>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2])|1[0-9]|[1-9]|\.){0,54})
-    \#(?<stich>(?:[HK][1-4KDPB]|[PX](?:[7-9KDPB]|10)|T(?:2[0-2]|1[0-9]|[1-9])|\.){0,4})$"#;
-static CARDS_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(CARDS_REGEX_STR).unwrap());
+// Splits a pile into card-like tokens, each of which is then parsed as a single card.
+const CARD_TOKEN_REGEX_STR: &str = r"[A-Za-z](?:[0-9]+|[A-Za-z])?|.";
+static CARD_TOKEN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(CARD_TOKEN_REGEX_STR).unwrap());
 
 const GAME_TYPE_REGEX_STR: &str = r#"(?ix)
     (?<game>SPD|SR|BR|BO|PD|SD|PO[1-3]|PB[1-3]|P[1-3]|[TRSBD])(?<player>[1-4])"#;
@@ -38,28 +36,41 @@ const GAME_REGEX_STR: &str = r#"(?ix)
     ^(?<gameAndPlayer>(?:(?:SPD|SR|BR|BO|PD|SD|PO[1-3]|PB[1-3]|P[1-3]|[TRSBD])[1-4]){1,4})(?<king>[HKPX]K|\-)(?<teammate>[1-4-])(?<talon>12|[12-])$"#;
 static GAME_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(GAME_REGEX_STR).unwrap());
 
-const CALL_REGEX_STR: &str = r#"(?ix)
-    (?<player0>1?2?3?4?T?U?K?V?)/(?<player1>1?2?3?4?T?U?K?V?)/(?<player2>1?2?3?4?T?U?K?V?)/(?<player3>1?2?3?4?T?U?K?V?)"#;
+const CALL_REGEX_STR: &str = r#"(?i)^1?2?3?4?T?U?K?V?$"#;
 static CALL_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(CALL_REGEX_STR).unwrap());
 
-#[derive(Clone, Copy, Eq)]
+// Trischaken cannot be gespritzt, so a `T` target always refers to the Trull.
+const SPRITZE_REGEX_STR: &str = r#"(?ix)
+    (?<target>SPD|SR|BR|BO|PD|SD|PO[1-3]|PB[1-3]|P[1-3]|[RSBD]|[1-4TUKV])(?<player>[1-4])(?<by>[1-4])(?<level>\+{0,2})"#;
+static SPRITZE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(SPRITZE_REGEX_STR).unwrap());
+
+const SPRITZEN_REGEX_STR: &str = r#"(?ix)
+    ^(?:(?:SPD|SR|BR|BO|PD|SD|PO[1-3]|PB[1-3]|P[1-3]|[RSBD]|[1-4TUKV])[1-4][1-4]\+{0,2})+$"#;
+static SPRITZEN_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(SPRITZEN_REGEX_STR).unwrap());
+
+/// Cards of a single pile, holding at most `N` cards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct CardCollection<const N: usize = NUM_CARDS> {
-    pub cards: [Option<Card>; N],
-    pub excluded: [Option<Card>; NUM_CARDS],
+    pub cards: CardSet,
+    pub excluded: CardSet,
 }
 
 impl<const N: usize> CardCollection<N> {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
-            cards: [None; N],
-            excluded: [None; NUM_CARDS],
+            cards: CardSet::new(),
+            excluded: CardSet::new(),
         }
     }
     pub fn contains(&self, card: Card) -> bool {
-        self.cards.contains(&Some(card))
+        self.cards.contains(card)
     }
     pub fn excludes(&self, card: Card) -> bool {
-        self.excluded.contains(&Some(card))
+        self.excluded.contains(card)
+    }
+    /// Maximum number of cards the collection can hold.
+    pub const fn capacity(&self) -> usize {
+        N
     }
 }
 
@@ -67,99 +78,72 @@ impl<const N: usize> TryFrom<&Vec<Card>> for CardCollection<N> {
     type Error = &'static str;
 
     fn try_from(value: &Vec<Card>) -> Result<Self, Self::Error> {
+        if value.len() > N {
+            return Err("Invalid vector length");
+        }
         Ok(CardCollection {
-            cards: value
-                .iter()
-                .map(|card| Some(*card))
-                .collect::<Vec<_>>()
-                .try_into()
-                .map_err(|_| "Invalid vector length")?,
-            excluded: [None; NUM_CARDS],
+            cards: value.iter().copied().collect(),
+            excluded: CardSet::new(),
         })
     }
 }
 
-impl<const N: usize> FromStr for CardCollection<N> {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl<const N: usize> CardCollection<N> {
+    /// Parses cards leniently, skipping anything that is not a card and dropping cards beyond the
+    /// first `N`.
+    ///
+    /// Only meant for sloppy input where a best guess is better than an error. Use [`FromStr`]
+    /// for everything else.
+    pub fn from_str_lenient(s: &str) -> Result<Self, TafError> {
         Ok(CardCollection::<N> {
             cards: CARD_REGEX
                 .find_iter(s)
+                .take(N)
                 .map(|mtch| {
                     Card::from_str(mtch.as_str())
-                        .map(|card| Some(card))
-                        .map_err(|_| "Invalid card")
+                        .map_err(|error| error.within(s, mtch.start(), None, None))
                 })
-                .chain(iter::repeat(Ok(None)))
-                .take(N)
-                .collect::<Result<Vec<_>, _>>()?
-                .try_into()
-                .unwrap(),
-            excluded: [None; NUM_CARDS],
+                .collect::<Result<CardSet, _>>()?,
+            excluded: CardSet::new(),
         })
     }
 }
 
-impl<const N: usize> PartialEq for CardCollection<N> {
-    fn eq(&self, other: &Self) -> bool {
-        let self_cards: HashSet<Option<Card>> = self.cards.try_into().unwrap();
-        let other_cards: HashSet<Option<Card>> = other.cards.try_into().unwrap();
-        let self_excluded: HashSet<Option<Card>> = self.excluded.try_into().unwrap();
-        let other_excluded: HashSet<Option<Card>> = other.excluded.try_into().unwrap();
-        let self_num_none = self.cards.iter().filter(|card| card.is_none()).count();
-        let other_num_none = other.cards.iter().filter(|card| card.is_none()).count();
-        self_cards == other_cards
-            && self_excluded == other_excluded
-            && self_num_none == other_num_none
+/// Parses cards strictly: every character must belong to a card, known cards must not repeat and
+/// there must be at most `N` of them. See [`CardCollection::from_str_lenient`] for the forgiving
+/// variant.
+impl<const N: usize> FromStr for CardCollection<N> {
+    type Err = TafError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CardCollection::<N> {
+            cards: parse_pile(s, (0, s), N, None, None)?.into_iter().collect(),
+            excluded: CardSet::new(),
+        })
     }
 }
 
-impl<const N: usize> Debug for CardCollection<N> {
+impl<const N: usize> Display for CardCollection<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let cards: Vec<String> = self
-            .cards
-            .iter()
-            .filter(|card| card.is_some())
-            .map(|card| match card {
-                Some(Card::UNKNOWN) => ".".to_string(),
-                Some(card) => format!("{card:?}"),
-                None => "".to_string(),
-            })
-            .collect();
-        let excluded: Vec<String> = self
-            .excluded
-            .iter()
-            .filter(|card| card.is_some())
-            .map(|card| match card {
-                Some(Card::UNKNOWN) => ".".to_string(),
-                Some(card) => format!("{card:?}"),
-                None => "".to_string(),
-            })
-            .collect();
-        write!(
-            f,
-            "CardCollection {{ cards: {} + {} None, excluded: {} + {} None }}",
-            cards.join(" "),
-            N - cards.len(),
-            excluded.join(" "),
-            NUM_CARDS - excluded.len(),
-        )
+        write!(f, "{}", self.cards)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Player {
-    pub hand: CardCollection<HAND_SIZE>,
+    pub hand: CardCollection<MAX_HAND_SIZE>,
     pub stiche: CardCollection,
     pub calls: Calls,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Calls {
     pub typ: Option<GameType>,
     pub called_king: Option<Card>,
-    pub taken_talon: Option<usize>,
+    /// Player who revealed themselves as partner by playing the called king, `None` while the
+    /// king has not been played.
+    pub teammate: Option<usize>,
+    pub taken_talon: TalonChoice,
     pub pagat: bool,
     pub uhu: bool,
     pub pelikan: bool,
@@ -168,144 +152,513 @@ pub struct Calls {
     pub kings: bool,
     pub ultimo: bool,
     pub valat: bool,
+    pub spritzen: Spritzen,
+}
+
+impl Calls {
+    pub fn announced(&self, ansage: Ansage) -> bool {
+        match ansage {
+            Ansage::Pagat => self.pagat,
+            Ansage::Uhu => self.uhu,
+            Ansage::Pelikan => self.pelikan,
+            Ansage::Quapil => self.quapil,
+            Ansage::Trull => self.trull,
+            Ansage::Kings => self.kings,
+            Ansage::Ultimo => self.ultimo,
+            Ansage::Valat => self.valat,
+        }
+    }
+
+    pub fn announce(&mut self, ansage: Ansage) {
+        match ansage {
+            Ansage::Pagat => self.pagat = true,
+            Ansage::Uhu => self.uhu = true,
+            Ansage::Pelikan => self.pelikan = true,
+            Ansage::Quapil => self.quapil = true,
+            Ansage::Trull => self.trull = true,
+            Ansage::Kings => self.kings = true,
+            Ansage::Ultimo => self.ultimo = true,
+            Ansage::Valat => self.valat = true,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GameState {
     pub players: [Player; NUM_PLAYERS],
-    pub stich: CardCollection<STICH_SIZE>,
-    pub talon: [CardCollection<3>; 2],
+    pub stich: Stich,
+    pub talon: [CardCollection<TALON_HALF_SIZE>; 2],
+    /// Index of the player who made the last stich, `None` if no stich has been made yet.
+    pub last_stich: Option<usize>,
     pub kleinen_stechen_großen: bool,
+    /// The Lizitation, `None` once it is over and the game has been declared.
+    pub bidding: Option<Bidding>,
+}
+
+impl GameState {
+    /// Index of the player who leads the current stich.
+    ///
+    /// The winner of the last stich leads the next one. Before the first stich, Vorhand
+    /// (player 1) leads.
+    pub fn leader(&self) -> usize {
+        self.last_stich.unwrap_or(0)
+    }
+
+    /// The game being played, `None` while no game has been declared.
+    pub fn game_type(&self) -> Option<GameType> {
+        self.players.iter().find_map(|player| player.calls.typ)
+    }
+
+    /// Index of the player whose turn it is to play into the current stich.
+    pub fn current_player(&self) -> usize {
+        (self.leader() + self.stich.len()) % NUM_PLAYERS
+    }
 }
 
 impl FromStr for GameState {
-    type Err = &'static str;
+    type Err = TafError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split: Vec<&str> = s.split_whitespace().take(5).collect();
+        let groups: Vec<(usize, &str)> = s
+            .split_whitespace()
+            .map(|group| (group.as_ptr() as usize - s.as_ptr() as usize, group))
+            .collect();
 
-        if let [cards_string, game_string, calls_string, kleinen_stechen_großen_string, spritzen_string] =
-            &split[..]
-        {
-            // Parse cards
-            let Some(captures) = CARDS_REGEX.captures(&cards_string) else {
-                return Err("Invalid TAF cards group");
-            };
+        let [cards_group, game_group, last_stich_group, calls_group, kleinen_stechen_großen_group, spritzen_group] =
+            groups[..]
+        else {
+            let start = groups
+                .get(6)
+                .map_or(s.trim_end().len(), |(offset, _)| *offset);
+            return Err(TafError::MissingGroups {
+                location: TafLocation::new(s, start..s.trim_end().len()),
+                found: groups.len(),
+            });
+        };
 
-            let talon0: CardCollection<3> = captures.name("talon0").unwrap().as_str().parse()?;
-            let talon1: CardCollection<3> = captures.name("talon1").unwrap().as_str().parse()?;
+        // Parse cards
+        let (cards_offset, cards_string) = cards_group;
+        let [talon_part, player0_part, player1_part, player2_part, player3_part, stich_part] =
+            split_with_offsets(cards_group, '#')[..]
+        else {
+            return Err(TafError::InvalidSyntax {
+                location: location(s, TafGroup::Cards, None, cards_offset, cards_string),
+                expected: "talon, four players and the current stich separated by `#`",
+            });
+        };
 
-            let mut players: [Player; 4] = [
-                ("player0hand", "player0stiche"),
-                ("player1hand", "player1stiche"),
-                ("player2hand", "player2stiche"),
-                ("player3hand", "player3stiche"),
-            ]
-            .iter()
-            .map(
-                |(hand_group, stiche_group)| -> Result<Player, &'static str> {
-                    Ok(Player {
-                        hand: captures.name(hand_group).unwrap().as_str().parse()?,
-                        stiche: captures.name(stiche_group).unwrap().as_str().parse()?,
-                        calls: Calls::default(),
-                    })
-                },
-            )
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .unwrap();
+        let [talon0_part, talon1_part] = split_with_offsets(talon_part, '/')[..] else {
+            return Err(TafError::InvalidSyntax {
+                location: location(s, TafGroup::Talon(0), None, talon_part.0, talon_part.1),
+                expected: "upper and lower talon separated by `/`",
+            });
+        };
+        let talon0 = parse_card_collection(s, talon0_part, TafGroup::Talon(0), None)?;
+        let talon1 = parse_card_collection(s, talon1_part, TafGroup::Talon(1), None)?;
+
+        let mut players: [Player; NUM_PLAYERS] = [Player {
+            hand: CardCollection::new(),
+            stiche: CardCollection::new(),
+            calls: Calls::default(),
+        }; NUM_PLAYERS];
+        for (index, player_part) in [player0_part, player1_part, player2_part, player3_part]
+            .into_iter()
+            .enumerate()
+        {
+            let [hand_part, stiche_part] = split_with_offsets(player_part, '/')[..] else {
+                return Err(TafError::InvalidSyntax {
+                    location: location(
+                        s,
+                        TafGroup::Hand,
+                        Some(index),
+                        player_part.0,
+                        player_part.1,
+                    ),
+                    expected: "hand and stiche separated by `/`",
+                });
+            };
+            players[index].hand = parse_card_collection(s, hand_part, TafGroup::Hand, Some(index))?;
+            players[index].stiche =
+                parse_card_collection(s, stiche_part, TafGroup::Stiche, Some(index))?;
+        }
 
-            let stich = captures.name("stich").unwrap().as_str().parse()?;
+        let stich: Stich = parse_pile(s, stich_part, STICH_SIZE, Some(TafGroup::Stich), None)?
+            .into_iter()
+            .collect();
 
-            // Parse game
-            let Some(captures) = GAME_REGEX.captures(&game_string) else {
-                return Err("Invalid TAF game group");
+        // Parse game, or the Lizitation while no game has been declared yet
+        let (game_offset, game_string) = game_group;
+        let mut bidding = None;
+        if let Some(bidding_string) = game_string
+            .strip_prefix('L')
+            .or_else(|| game_string.strip_prefix('l'))
+        {
+            bidding =
+                Some(Bidding::from_str(bidding_string).map_err(|error| {
+                    error.within(s, game_offset + 1, Some(TafGroup::Game), None)
+                })?);
+        } else {
+            let Some(captures) = GAME_REGEX.captures(game_string) else {
+                return Err(TafError::InvalidSyntax {
+                    location: location(s, TafGroup::Game, None, game_offset, game_string),
+                    expected:
+                        "game types and players, called king, teammate and talon, or the Lizitation",
+                });
             };
 
-            let king: Option<Card> = match captures.name("king").unwrap().as_str() {
+            let king: Option<Card> = match &captures["king"] {
                 "-" => None,
-                s => Some(Card::from_str(s)?),
+                king => Some(Card::from_str(king).unwrap()),
             };
-            let teammate: Option<usize> = match captures.name("teammate").unwrap().as_str() {
+            let teammate: Option<usize> = match &captures["teammate"] {
                 "-" => None,
-                s => Some(s.parse().map_err(|_| "Invalid teammate")?),
+                teammate => Some(teammate.parse::<usize>().unwrap() - 1),
             };
-            let talon: Option<usize> = match captures.name("talon").unwrap().as_str() {
+            let talon: Option<TalonChoice> = match &captures["talon"] {
                 "-" => None,
-                s => Some(s.parse().map_err(|_| "Invalid talon")?),
+                talon => Some(talon.parse().unwrap()),
             };
 
-            let game_and_player: &str = captures.name("gameAndPlayer").unwrap().as_str();
+            let game_and_player: &str = &captures["gameAndPlayer"];
             for game_and_player in GAME_TYPE_REGEX.captures_iter(game_and_player) {
-                let game_type: GameType = game_and_player.name("game").unwrap().as_str().parse()?;
-                let player: usize = game_and_player
-                    .name("player")
-                    .unwrap()
-                    .as_str()
-                    .parse()
-                    .map_err(|_| "Invalid game player index")?;
-
-                if player < 1 || player > NUM_PLAYERS {
-                    return Err("Invalid game player index");
+                let game_type: GameType = game_and_player["game"].parse().unwrap();
+                let player: usize = game_and_player["player"].parse::<usize>().unwrap() - 1;
+
+                players[player].calls.typ = Some(game_type);
+                players[player].calls.called_king = king;
+                players[player].calls.teammate = teammate;
+                players[player].calls.taken_talon =
+                    talon.unwrap_or(TalonChoice::untaken(game_type));
+            }
+        }
+
+        // Parse last stich
+        let (last_stich_offset, last_stich_string) = last_stich_group;
+        let last_stich: Option<usize> = match last_stich_string {
+            "-" => None,
+            last_stich => match last_stich.parse::<usize>() {
+                Ok(player) if (1..=NUM_PLAYERS).contains(&player) => Some(player - 1),
+                _ => {
+                    return Err(TafError::InvalidSyntax {
+                        location: location(
+                            s,
+                            TafGroup::LastStich,
+                            None,
+                            last_stich_offset,
+                            last_stich,
+                        ),
+                        expected: "`-` or the player who made the last stich",
+                    })
                 }
-                players[player - 1].calls.typ = Some(game_type);
-                players[player - 1].calls.called_king = king;
-                players[player - 1].calls.taken_talon = talon;
+            },
+        };
+
+        // Parse calls
+        let (calls_offset, calls_string) = calls_group;
+        let calls_parts = split_with_offsets(calls_group, '/');
+        if calls_parts.len() != NUM_PLAYERS {
+            return Err(TafError::InvalidSyntax {
+                location: location(s, TafGroup::Calls, None, calls_offset, calls_string),
+                expected: "ansagen of four players separated by `/`",
+            });
+        }
+        for (index, (part_offset, part)) in calls_parts.into_iter().enumerate() {
+            for (char_offset, c) in part.char_indices() {
+                let ansage_string = &part[char_offset..char_offset + c.len_utf8()];
+                let ansage = Ansage::from_str(ansage_string).map_err(|error| {
+                    error.within(
+                        s,
+                        part_offset + char_offset,
+                        Some(TafGroup::Calls),
+                        Some(index),
+                    )
+                })?;
+                players[index].calls.announce(ansage);
+            }
+            if !CALL_REGEX.is_match(part) {
+                return Err(TafError::InvalidSyntax {
+                    location: location(s, TafGroup::Calls, Some(index), part_offset, part),
+                    expected: "each ansage at most once, in the order `1234TUKV`",
+                });
             }
+        }
 
-            // Parse calls
-            let Some(captures) = CALL_REGEX.captures(&calls_string) else {
-                return Err("Invalid TAF calls group");
-            };
+        // Parse kleinen_stechen_großen
+        let (kleinen_stechen_großen_offset, kleinen_stechen_großen_string) =
+            kleinen_stechen_großen_group;
+        let kleinen_stechen_großen: bool = match kleinen_stechen_großen_string {
+            "J" | "j" => true,
+            "-" => false,
+            kleinen_stechen_großen => {
+                return Err(TafError::InvalidSyntax {
+                    location: location(
+                        s,
+                        TafGroup::KleinenStechenGroßen,
+                        None,
+                        kleinen_stechen_großen_offset,
+                        kleinen_stechen_großen,
+                    ),
+                    expected: "`J` or `-`",
+                })
+            }
+        };
 
-            [
-                ("player0", 0),
-                ("player1", 1),
-                ("player2", 2),
-                ("player3", 3),
-            ]
-            .iter()
-            .map(|(player_group, player_index)| {
-                captures
-                    .name(player_group)
-                    .unwrap()
-                    .as_str()
-                    .chars()
-                    .map(|c| {
-                        match c {
-                            '1' => players[*player_index].calls.pagat = true,
-                            '2' => players[*player_index].calls.uhu = true,
-                            '3' => players[*player_index].calls.pelikan = true,
-                            '4' => players[*player_index].calls.quapil = true,
-                            'T' => players[*player_index].calls.trull = true,
-                            'K' => players[*player_index].calls.kings = true,
-                            'U' => players[*player_index].calls.ultimo = true,
-                            'V' => players[*player_index].calls.valat = true,
-                            _ => return Err("Invalid call"),
+        // Parse spritzen
+        let (spritzen_offset, spritzen_string) = spritzen_group;
+        if spritzen_string != "-" {
+            if !SPRITZEN_REGEX.is_match(spritzen_string) {
+                return Err(TafError::InvalidSyntax {
+                    location: location(
+                        s,
+                        TafGroup::Spritzen,
+                        None,
+                        spritzen_offset,
+                        spritzen_string,
+                    ),
+                    expected: "`-` or spritzen like `R12`",
+                });
+            }
+
+            for spritze in SPRITZE_REGEX.captures_iter(spritzen_string) {
+                let player: usize = spritze["player"].parse::<usize>().unwrap() - 1;
+                let by: usize = spritze["by"].parse::<usize>().unwrap() - 1;
+                let calls = &mut players[player].calls;
+                let mtch = spritze.get(0).unwrap();
+                let invalid_spritze = |reason| TafError::InvalidSpritze {
+                    location: location(
+                        s,
+                        TafGroup::Spritzen,
+                        Some(player),
+                        spritzen_offset + mtch.start(),
+                        mtch.as_str(),
+                    ),
+                    reason,
+                };
+
+                let target = match spritze["target"].parse::<Ansage>() {
+                    Ok(ansage) if calls.announced(ansage) => SpritzenTarget::Ansage(ansage),
+                    Ok(_) => return Err(invalid_spritze("the ansage was not announced")),
+                    Err(_) => {
+                        let game_type: GameType = spritze["target"].parse().unwrap();
+                        if calls.typ != Some(game_type) {
+                            return Err(invalid_spritze("the game is not played by this player"));
                         }
-                        Ok(())
-                    })
-                    .collect::<Result<Vec<_>, _>>()
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+                        SpritzenTarget::Game
+                    }
+                };
+                if by == player {
+                    return Err(invalid_spritze(
+                        "players cannot spritzen their own game or ansage",
+                    ));
+                }
+                if calls.spritzen.get(target).is_some() {
+                    return Err(invalid_spritze("it was already gespritzt"));
+                }
 
-            // Parse kleinen_stechen_großen
-            let kleinen_stechen_großen: bool = match kleinen_stechen_großen_string {
-                &"J" | &"j" => true,
-                &"-" => false,
-                _ => return Err("Invalid kleinen_stechen_großen"),
-            };
+                let level = match spritze["level"].len() {
+                    0 => SpritzenLevel::Kontra,
+                    1 => SpritzenLevel::Rekontra,
+                    _ => SpritzenLevel::Subkontra,
+                };
+                calls.spritzen.set(target, Some(Spritze { by, level }));
+            }
+        }
 
-            // TODO: Parse spritzen
+        let mut game_state = GameState {
+            players,
+            stich,
+            talon: [talon0, talon1],
+            last_stich,
+            kleinen_stechen_großen,
+            bidding,
+        };
+        game_state.update_exclusions();
+        Ok(game_state)
+    }
+}
 
-            Ok(GameState {
-                players,
-                stich,
-                talon: [talon0, talon1],
-                kleinen_stechen_großen,
+fn location(
+    input: &str,
+    group: TafGroup,
+    player: Option<usize>,
+    offset: usize,
+    text: &str,
+) -> TafLocation {
+    TafLocation {
+        input: input.to_string(),
+        group: Some(group),
+        player,
+        span: offset..offset + text.len(),
+    }
+}
+
+/// Splits a part of a TAF string at `separator`, keeping track of the byte offset of each piece.
+fn split_with_offsets((offset, s): (usize, &str), separator: char) -> Vec<(usize, &str)> {
+    let mut offset = offset;
+    s.split(separator)
+        .map(|piece| {
+            let piece_offset = offset;
+            offset += piece.len() + separator.len_utf8();
+            (piece_offset, piece)
+        })
+        .collect()
+}
+
+/// Parses a single pile of the TAF cards group, rejecting anything that is not a card and known
+/// cards that appear twice.
+fn parse_pile(
+    input: &str,
+    (offset, pile): (usize, &str),
+    max: usize,
+    group: Option<TafGroup>,
+    player: Option<usize>,
+) -> Result<Vec<Card>, TafError> {
+    let location = |start: usize, text: &str| TafLocation {
+        input: input.to_string(),
+        group,
+        player,
+        span: offset + start..offset + start + text.len(),
+    };
+
+    let mut cards = Vec::new();
+    let mut seen = CardSet::new();
+    for (index, token) in CARD_TOKEN_REGEX.find_iter(pile).enumerate() {
+        if index == max {
+            return Err(TafError::TooManyCards {
+                location: location(token.start(), &pile[token.start()..]),
+                max,
+            });
+        }
+        let card = Card::from_str(token.as_str())
+            .map_err(|error| error.within(input, offset + token.start(), group, player))?;
+        if !seen.insert(card) {
+            return Err(TafError::DuplicateCard {
+                location: location(token.start(), token.as_str()),
+            });
+        }
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+fn parse_card_collection<const N: usize>(
+    input: &str,
+    pile: (usize, &str),
+    group: TafGroup,
+    player: Option<usize>,
+) -> Result<CardCollection<N>, TafError> {
+    Ok(CardCollection {
+        cards: parse_pile(input, pile, N, Some(group), player)?
+            .into_iter()
+            .collect(),
+        excluded: CardSet::new(),
+    })
+}
+
+/// Writes the game state as canonical TAF.
+///
+/// Game states without any declarer and without a Lizitation have no TAF representation, since
+/// the game group requires one of them.
+///
+/// Excluded cards are not part of TAF. Parsing only restores what can be inferred from the state
+/// itself, see [`GameState::update_exclusions`].
+impl Display for GameState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Write cards
+        write!(f, "{}/{}", self.talon[0], self.talon[1])?;
+        for player in &self.players {
+            write!(f, "#{}/{}", player.hand, player.stiche)?;
+        }
+        write!(f, "#{} ", self.stich)?;
+
+        // Write game
+        if let Some(bidding) = self.bidding {
+            write!(f, "L{bidding} ")?;
+        } else {
+            let mut declarer_calls = None;
+            for (index, player) in self.players.iter().enumerate() {
+                if let Some(typ) = player.calls.typ {
+                    write!(f, "{typ}{}", index + 1)?;
+                    declarer_calls.get_or_insert(player.calls);
+                }
+            }
+            let declarer_calls = declarer_calls.unwrap_or_default();
+            match declarer_calls.called_king {
+                Some(king) => write!(f, "{king}")?,
+                None => write!(f, "-")?,
+            }
+            match declarer_calls.teammate {
+                Some(teammate) => write!(f, "{}", teammate + 1)?,
+                None => write!(f, "-")?,
+            }
+            write!(f, "{} ", declarer_calls.taken_talon)?;
+        }
+
+        // Write last stich
+        match self.last_stich {
+            Some(player) => write!(f, "{} ", player + 1)?,
+            None => write!(f, "- ")?,
+        }
+
+        // Write calls
+        let calls: Vec<String> = self
+            .players
+            .iter()
+            .map(|player| {
+                Ansage::ALL
+                    .iter()
+                    .filter(|ansage| player.calls.announced(**ansage))
+                    .map(|ansage| ansage.to_string())
+                    .collect()
             })
+            .collect();
+        write!(f, "{} ", calls.join("/"))?;
+
+        // Write kleinen_stechen_großen
+        write!(
+            f,
+            "{} ",
+            if self.kleinen_stechen_großen {
+                "J"
+            } else {
+                "-"
+            }
+        )?;
+
+        // Write spritzen
+        let mut spritzen = String::new();
+        for (index, player) in self.players.iter().enumerate() {
+            let targets = iter::once(SpritzenTarget::Game).chain(
+                Ansage::ALL
+                    .iter()
+                    .map(|ansage| SpritzenTarget::Ansage(*ansage)),
+            );
+            for target in targets {
+                let Some(spritze) = player.calls.spritzen.get(target) else {
+                    continue;
+                };
+                match target {
+                    SpritzenTarget::Game => {
+                        spritzen += &player
+                            .calls
+                            .typ
+                            .map(|typ| typ.to_string())
+                            .unwrap_or_default()
+                    }
+                    SpritzenTarget::Ansage(ansage) => spritzen += &ansage.to_string(),
+                }
+                spritzen += &format!("{}{}", index + 1, spritze.by + 1);
+                spritzen += match spritze.level {
+                    SpritzenLevel::Kontra => "",
+                    SpritzenLevel::Rekontra => "+",
+                    SpritzenLevel::Subkontra => "++",
+                };
+            }
+        }
+        if spritzen.is_empty() {
+            write!(f, "-")
         } else {
-            Err("Missing TAF groups")
+            write!(f, "{spritzen}")
         }
     }
 }
@@ -313,44 +666,123 @@ impl FromStr for GameState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::strategies::{arb_card_collection, arb_game_state};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn card_collection_display_round_trip(card_collection in arb_card_collection::<12>()) {
+            prop_assert_eq!(
+                CardCollection::<12>::from_str(&card_collection.to_string()),
+                Ok(card_collection)
+            );
+        }
+
+        #[test]
+        fn game_state_display_round_trip(game_state in arb_game_state()) {
+            prop_assert_eq!(GameState::from_str(&game_state.to_string()), Ok(game_state));
+        }
+    }
+
+    #[test]
+    fn game_state_display() {
+        for taf in [
+            "XKX10X9/H2H3H4#HKHDKPKBK1K2K3K4T1T3T5T6/#............/#............/#............/# R1XK-1 - 1K/T// - R12",
+            ".../...#HDKPKBK1K2K3K4T1T3T5T6/#.........../#.........../#.........../#HKX8T22T21 R1XK-1 - 1K/T// J -",
+            ".../...#HDKPKBK1K2K3K4T1T3T5T6/#.........../#.........../#.........../#X8HKT22T21 R1HK21 - 1K/T// J -",
+            ".../...#T1T3/HKHDX8T22#../XKT5T6T21#../T10T11T12T14#../K1K2K3K4#PK D2--2 2 /1234TUKV// - D21+124++",
+            ".../...#......../#......../#......../#......../# PB11PB32--- - /// - -",
+            ".../...#............/#............/#............/#............/# L4:R/x/PB1/- - /// - -",
+        ] {
+            assert_eq!(GameState::from_str(taf).unwrap().to_string(), taf);
+        }
+
+        // The teammate is stored with the calls of the declarer
+        let game_state = GameState::from_str(
+            ".../...#HDKPKBK1K2K3K4T1T3T5T6/#.........../#.........../#.........../#X8HKT22T21 R1HK21 - 1K/T// J -",
+        )
+        .unwrap();
+        assert_eq!(game_state.players[0].calls.teammate, Some(1));
+
+        // Cards are written in deck order, the current stich in the order it was played
+        assert_eq!(
+            GameState::from_str(
+                "xkx10x9/h4h3h2#hdt1t3t5t6k1k2k3k4kbkp/#............/#............/#............/#t22hk r1xk-1 - 1k/t// - r12"
+            )
+            .unwrap()
+            .to_string(),
+            "XKX10X9/H2H3H4#HDKPKBK1K2K3K4T1T3T5T6/#............/#............/#............/#T22HK R1XK-1 - 1K/T// - R12"
+        );
+    }
 
     #[test]
     fn card_collection_from_str() {
         assert_eq!(
             CardCollection::<3>::from_str("H1H2H3").unwrap().cards,
-            [Some(Card::H1), Some(Card::H2), Some(Card::H3)],
+            CardSet::from_iter([Card::H1, Card::H2, Card::H3]),
         );
         assert_eq!(
             CardCollection::<3>::from_str("H1H2").unwrap().cards,
-            [Some(Card::H1), Some(Card::H2), None],
-        );
-        assert_eq!(
-            CardCollection::<3>::from_str("H1H2H3H4").unwrap().cards,
-            [Some(Card::H1), Some(Card::H2), Some(Card::H3)],
+            CardSet::from_iter([Card::H1, Card::H2]),
         );
+        assert!(matches!(
+            CardCollection::<3>::from_str("H1H2H3H4"),
+            Err(TafError::TooManyCards { max: 3, .. })
+        ));
         assert_eq!(
             CardCollection::<3>::from_str("XKXBXD").unwrap().cards,
-            [Some(Card::XK), Some(Card::XB), Some(Card::XD)],
+            CardSet::from_iter([Card::XK, Card::XB, Card::XD]),
         );
         assert_ne!(
             CardCollection::<3>::from_str("XKXBXD").unwrap().cards,
-            [Some(Card::HK), Some(Card::HK), Some(Card::UNKNOWN)],
+            CardSet::from_iter([Card::HK, Card::HK, Card::UNKNOWN]),
         );
         assert_eq!(
             CardCollection::<12>::from_str("").unwrap().cards,
-            [None; 12]
-        );
-        assert_eq!(
-            CardCollection::<12>::from_str("asdaP07sd").unwrap().cards,
-            [None; 12]
+            CardSet::new()
         );
+        assert!(matches!(
+            CardCollection::<12>::from_str("asdaP07sd"),
+            Err(TafError::InvalidCard { .. })
+        ));
+        assert!(matches!(
+            CardCollection::<12>::from_str("HKHK"),
+            Err(TafError::DuplicateCard { .. })
+        ));
         assert_eq!(
             CardCollection::<6>::from_str("......").unwrap().cards,
-            [Some(Card::UNKNOWN); 6]
+            CardSet::from_iter([Card::UNKNOWN; 6])
         );
         assert_eq!(
             CardCollection::<6>::from_str("P9P8P7").unwrap().excluded,
-            [None; 54]
+            CardSet::new()
+        );
+
+        let error = CardCollection::<3>::from_str("H1H2H3H4H5").unwrap_err();
+        assert_eq!(error.location().text(), "H4H5");
+        let error = CardCollection::<12>::from_str("H1 H2").unwrap_err();
+        assert_eq!(error.location().span, 2..3);
+    }
+
+    #[test]
+    fn card_collection_from_str_lenient() {
+        assert_eq!(
+            CardCollection::<3>::from_str_lenient("H1H2H3H4")
+                .unwrap()
+                .cards,
+            CardSet::from_iter([Card::H1, Card::H2, Card::H3]),
+        );
+        assert_eq!(
+            CardCollection::<12>::from_str_lenient("asdaP07sd")
+                .unwrap()
+                .cards,
+            CardSet::new()
+        );
+        assert_eq!(
+            CardCollection::<12>::from_str_lenient("H1, H2 T22")
+                .unwrap()
+                .cards,
+            CardSet::from_iter([Card::H1, Card::H2, Card::T22]),
         );
     }
 
@@ -368,10 +800,7 @@ mod tests {
             CardCollection::<3>::from_str("H1H2H3").unwrap(),
             CardCollection::<3>::from_str("H1H2H4").unwrap(),
         );
-        assert_eq!(
-            CardCollection::<3>::from_str("H1H2H3.").unwrap(),
-            CardCollection::<3>::from_str("H1H2H3.").unwrap(),
-        );
+        assert!(CardCollection::<3>::from_str("H1H2H3.").is_err());
         assert_eq!(
             CardCollection::<4>::from_str("H1H2H3.").unwrap(),
             CardCollection::<4>::from_str("H1H2H3.").unwrap(),
@@ -409,52 +838,354 @@ mod tests {
     #[test]
     fn game_state_from_str() {
         // Start of Rufer
+        let mut expected = GameState {
+            players: [
+                Player {
+                    hand: CardCollection::from_str("hdt1t3t5t6k1k2k3k4kbkp").unwrap(),
+                    stiche: CardCollection::new(),
+                    calls: Calls {
+                        typ: Some(GameType::R),
+                        taken_talon: TalonChoice::Upper,
+                        pagat: true,
+                        kings: true,
+                        called_king: Some(Card::XK),
+                        ..Default::default()
+                    },
+                },
+                Player {
+                    hand: CardCollection::from_str("...........").unwrap(),
+                    stiche: CardCollection::new(),
+                    calls: Calls {
+                        trull: true,
+                        ..Default::default()
+                    },
+                },
+                Player {
+                    hand: CardCollection::from_str("...........").unwrap(),
+                    stiche: CardCollection::new(),
+                    calls: Calls::default(),
+                },
+                Player {
+                    hand: CardCollection::from_str("...........").unwrap(),
+                    stiche: CardCollection::new(),
+                    calls: Calls::default(),
+                },
+            ],
+            stich: Stich::from_iter([Card::HK, Card::X8, Card::T22, Card::T21]),
+            talon: [
+                CardCollection::<3>::from_str("...").unwrap(),
+                CardCollection::<3>::from_str("...").unwrap(),
+            ],
+            last_stich: None,
+            kleinen_stechen_großen: true,
+            bidding: None,
+        };
+        expected.update_exclusions();
+
         assert_eq!(
             GameState::from_str(
-                ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 1K/T// j -"
+                ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j -"
             )
             .unwrap(),
-            GameState {
-                players: [
-                    Player {
-                        hand: CardCollection::from_str("hdt1t3t5t6k1k2k3k4kbkp").unwrap(),
-                        stiche: CardCollection::new(),
-                        calls: Calls {
-                            typ: Some(GameType::R),
-                            taken_talon: Some(1),
-                            pagat: true,
-                            kings: true,
-                            called_king: Some(Card::XK),
-                            ..Default::default()
-                        },
-                    },
-                    Player {
-                        hand: CardCollection::from_str("...........").unwrap(),
-                        stiche: CardCollection::new(),
-                        calls: Calls {
-                            trull: true,
-                            ..Default::default()
-                        }
-                    },
-                    Player {
-                        hand: CardCollection::from_str("...........").unwrap(),
-                        stiche: CardCollection::new(),
-                        calls: Calls::default(),
-                    },
-                    Player {
-                        hand: CardCollection::from_str("...........").unwrap(),
-                        stiche: CardCollection::new(),
-                        calls: Calls::default(),
-                    },
-                ],
-                stich: CardCollection::<4>::from_str("HKX8T22T21").unwrap(),
-                talon: [
-                    CardCollection::<3>::from_str("...").unwrap(),
-                    CardCollection::<3>::from_str("...").unwrap(),
-                ],
-                kleinen_stechen_großen: true,
+            expected
+        );
+    }
+
+    #[test]
+    fn game_state_from_str_spec_example() {
+        let game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#HkHdT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - 1K/T// - R12",
+        )
+        .unwrap();
+
+        assert_eq!(
+            game_state.talon,
+            [
+                CardCollection::<3>::from_str("XKX10X9").unwrap(),
+                CardCollection::<3>::from_str("H4H3H2").unwrap(),
+            ]
+        );
+        assert_eq!(
+            game_state.players[0].hand.cards,
+            CardCollection::<12>::from_str("HKHDT1T3T5T6K1K2K3K4KBKP")
+                .unwrap()
+                .cards
+        );
+        assert_eq!(game_state.players[0].calls.typ, Some(GameType::R));
+        assert_eq!(game_state.players[0].calls.called_king, Some(Card::XK));
+        assert_eq!(game_state.last_stich, None);
+        assert_eq!(game_state.leader(), 0);
+        assert!(!game_state.kleinen_stechen_großen);
+    }
+
+    #[test]
+    fn game_state_from_str_last_stich() {
+        let taf = |last_stich: &str| {
+            format!(
+                ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 {last_stich} 1K/T// j -"
+            )
+        };
+
+        assert_eq!(GameState::from_str(&taf("-")).unwrap().last_stich, None);
+        assert_eq!(GameState::from_str(&taf("1")).unwrap().last_stich, Some(0));
+        assert_eq!(GameState::from_str(&taf("2")).unwrap().last_stich, Some(1));
+        assert_eq!(GameState::from_str(&taf("3")).unwrap().last_stich, Some(2));
+        assert_eq!(GameState::from_str(&taf("4")).unwrap().last_stich, Some(3));
+        assert_eq!(GameState::from_str(&taf("4")).unwrap().leader(), 3);
+
+        assert!(matches!(
+            GameState::from_str(&taf("0")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::LastStich),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("5")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::LastStich),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("x")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::LastStich),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(
+                ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 1K/T// j -"
+            ),
+            Err(TafError::MissingGroups { found: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn game_state_from_str_spritzen() {
+        let taf = |spritzen: &str| {
+            format!(
+                ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j {spritzen}"
+            )
+        };
+
+        let game_state = GameState::from_str(&taf("-")).unwrap();
+        assert!(game_state
+            .players
+            .iter()
+            .all(|player| player.calls.spritzen.is_empty()));
+
+        let game_state = GameState::from_str(&taf("R12")).unwrap();
+        assert_eq!(
+            game_state.players[0].calls.spritzen.game,
+            Some(Spritze {
+                by: 1,
+                level: SpritzenLevel::Kontra
+            })
+        );
+        assert_eq!(
+            game_state.players[0]
+                .calls
+                .spritzen
+                .multiplier(SpritzenTarget::Game),
+            2
+        );
+
+        let game_state = GameState::from_str(&taf("r13+112++T21")).unwrap();
+        assert_eq!(
+            game_state.players[0].calls.spritzen.game,
+            Some(Spritze {
+                by: 2,
+                level: SpritzenLevel::Rekontra
+            })
+        );
+        assert_eq!(
+            game_state.players[0]
+                .calls
+                .spritzen
+                .get(SpritzenTarget::Ansage(Ansage::Pagat)),
+            Some(Spritze {
+                by: 1,
+                level: SpritzenLevel::Subkontra
+            })
+        );
+        assert_eq!(
+            game_state.players[1]
+                .calls
+                .spritzen
+                .get(SpritzenTarget::Ansage(Ansage::Trull)),
+            Some(Spritze {
+                by: 0,
+                level: SpritzenLevel::Kontra
+            })
+        );
+        assert_eq!(
+            game_state.players[0]
+                .calls
+                .spritzen
+                .multiplier(SpritzenTarget::Ansage(Ansage::Kings)),
+            1
+        );
+
+        assert!(matches!(
+            GameState::from_str(&taf("R12+++")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::Spritzen),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("X12")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::Spritzen),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("R1")),
+            Err(TafError::InvalidSyntax {
+                location: TafLocation {
+                    group: Some(TafGroup::Spritzen),
+                    ..
+                },
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("D12")),
+            Err(TafError::InvalidSpritze {
+                reason: "the game is not played by this player",
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("R22")),
+            Err(TafError::InvalidSpritze {
+                reason: "the game is not played by this player",
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("T12")),
+            Err(TafError::InvalidSpritze {
+                reason: "the ansage was not announced",
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("R11")),
+            Err(TafError::InvalidSpritze {
+                reason: "players cannot spritzen their own game or ansage",
+                ..
+            })
+        ));
+        assert!(matches!(
+            GameState::from_str(&taf("R12R13")),
+            Err(TafError::InvalidSpritze {
+                reason: "it was already gespritzt",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn game_state_from_str_errors() {
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.....x1..../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j -",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.location(),
+            &TafLocation {
+                input: ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.....x1..../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j -".to_string(),
+                group: Some(TafGroup::Hand),
+                player: Some(1),
+                span: 37..39,
             }
         );
+        assert_eq!(
+            error.to_string(),
+            "invalid card `x1` in hand of player 2\n\
+             .../...#hdt1t3t5t6k1k2k3k4kbkp/#.....x1..../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j -\n\
+             \x20                                    ^^"
+        );
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21t20 R1XK-1 - 1K/T// j -",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::TooManyCards { max: 4, .. }));
+        assert_eq!(error.location().group, Some(TafGroup::Stich));
+        assert_eq!(error.location().text(), "t20");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22hk R1XK-1 - 1K/T// j -",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::DuplicateCard { .. }));
+        assert_eq!(error.location().group, Some(TafGroup::Stich));
+        assert_eq!(error.location().text(), "hk");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/TX// j -",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::InvalidAnsage { .. }));
+        assert_eq!(error.location().player, Some(1));
+        assert_eq!(error.location().text(), "X");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - K1/T// j -",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::InvalidSyntax { .. }));
+        assert_eq!(error.location().player, Some(0));
+        assert_eq!(error.location().text(), "K1");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j -",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::InvalidSyntax { .. }));
+        assert_eq!(error.location().player, Some(0));
+        assert_eq!(error.location().text(), "hdt1t3t5t6k1k2k3k4kbkp");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 Q1XK-1 - 1K/T// j -",
+        )
+        .unwrap_err();
+        assert_eq!(error.location().group, Some(TafGroup::Game));
+        assert_eq!(error.location().text(), "Q1XK-1");
+
+        let error = GameState::from_str(
+            ".../...#............/#............/#............/#............/# L5:-/-/-/- - /// - -",
+        )
+        .unwrap_err();
+        assert_eq!(error.location().group, Some(TafGroup::Game));
+        assert_eq!(error.location().text(), "5:-/-/-/-");
+
+        let error = GameState::from_str(
+            ".../...#hdt1t3t5t6k1k2k3k4kbkp/#.........../#.........../#.........../#hkx8t22t21 R1XK-1 - 1K/T// j R12 extra",
+        )
+        .unwrap_err();
+        assert!(matches!(error, TafError::MissingGroups { found: 7, .. }));
+        assert_eq!(error.location().text(), "extra");
     }
 
     #[test]
@@ -463,62 +1194,66 @@ mod tests {
             GameState {
                 players: [
                     Player {
-                        hand: CardCollection::<12>::from_str("H1H3T5T6K1K2K3K4KBKP....").unwrap(),
+                        hand: CardCollection::from_str("H1H3T5T6K1K2K3K4KBKP..").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("H8T22T21").unwrap(),
+                        hand: CardCollection::from_str("T22T21").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("HK").unwrap(),
+                        hand: CardCollection::from_str("HK").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("HX").unwrap(),
+                        hand: CardCollection::from_str("").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                 ],
-                stich: CardCollection::<4>::from_str("T12....").unwrap(),
+                stich: Stich::from_iter([Card::T12, Card::UNKNOWN, Card::UNKNOWN, Card::UNKNOWN]),
                 talon: [
-                    CardCollection::<3>::from_str("HDT").unwrap(),
-                    CardCollection::<3>::from_str("HDT").unwrap(),
+                    CardCollection::<3>::from_str("HD").unwrap(),
+                    CardCollection::<3>::from_str("HD").unwrap(),
                 ],
+                last_stich: None,
                 kleinen_stechen_großen: true,
+                bidding: None,
             },
             GameState {
                 players: [
                     Player {
-                        hand: CardCollection::<12>::from_str("H1H3T5T6K1K2K3K4KBKP....").unwrap(),
+                        hand: CardCollection::from_str("H1H3T5T6K1K2K3K4KBKP..").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("H8T22T21").unwrap(),
+                        hand: CardCollection::from_str("T22T21").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("HK").unwrap(),
+                        hand: CardCollection::from_str("HK").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                     Player {
-                        hand: CardCollection::<12>::from_str("HX").unwrap(),
+                        hand: CardCollection::from_str("").unwrap(),
                         stiche: CardCollection::<54>::from_str("..............").unwrap(),
                         calls: Calls::default(),
                     },
                 ],
-                stich: CardCollection::<4>::from_str("T12....").unwrap(),
+                stich: Stich::from_iter([Card::T12, Card::UNKNOWN, Card::UNKNOWN, Card::UNKNOWN]),
                 talon: [
-                    CardCollection::<3>::from_str("HDT").unwrap(),
-                    CardCollection::<3>::from_str("HDT").unwrap(),
+                    CardCollection::<3>::from_str("HD").unwrap(),
+                    CardCollection::<3>::from_str("HD").unwrap(),
                 ],
+                last_stich: None,
                 kleinen_stechen_großen: true,
+                bidding: None,
             }
         );
     }
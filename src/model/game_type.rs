@@ -1,6 +1,13 @@
+use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+use super::error::{TafError, TafLocation};
+
+pub const NUM_GAME_TYPES: usize = 20;
+
+/// Games in the order of their rank in the Lizitation.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum GameType {
     T,
     R,
@@ -24,8 +31,147 @@ pub enum GameType {
     SPD,
 }
 
+impl GameType {
+    /// All games, from the lowest to the highest.
+    pub const ALL: [GameType; NUM_GAME_TYPES] = [
+        GameType::T,
+        GameType::R,
+        GameType::S,
+        GameType::P1,
+        GameType::P2,
+        GameType::P3,
+        GameType::SR,
+        GameType::PB1,
+        GameType::PB2,
+        GameType::PB3,
+        GameType::B,
+        GameType::BR,
+        GameType::PO1,
+        GameType::PO2,
+        GameType::PO3,
+        GameType::D,
+        GameType::BO,
+        GameType::PD,
+        GameType::SD,
+        GameType::SPD,
+    ];
+
+    /// Dense index of the game, in the order of [`GameType::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Base value of the game under Wiener rules, paid by every opponent of the declarer.
+    ///
+    /// See [`Ruleset`](super::ruleset::Ruleset) for other tariffs.
+    pub fn value(&self) -> u32 {
+        match self {
+            GameType::T | GameType::R => 1,
+            GameType::S
+            | GameType::P1
+            | GameType::P2
+            | GameType::P3
+            | GameType::SR
+            | GameType::PB1
+            | GameType::PB2
+            | GameType::PB3 => 2,
+            GameType::B | GameType::BR => 3,
+            GameType::PO1 | GameType::PO2 | GameType::PO3 | GameType::D => 4,
+            GameType::BO | GameType::PD => 5,
+            GameType::SD => 6,
+            GameType::SPD => 7,
+        }
+    }
+
+    /// Whether the declarer calls a king to find a partner.
+    pub fn calls_king(&self) -> bool {
+        matches!(self, GameType::R | GameType::SR | GameType::BR)
+    }
+
+    /// Whether the declarer takes one half of the talon.
+    pub fn takes_talon_half(&self) -> bool {
+        matches!(
+            self,
+            GameType::R | GameType::BR | GameType::D | GameType::PD
+        )
+    }
+
+    /// Whether the declarer takes the whole talon.
+    pub fn takes_whole_talon(&self) -> bool {
+        matches!(self, GameType::S)
+    }
+
+    /// Whether the declarer takes cards from the talon.
+    pub fn takes_talon(&self) -> bool {
+        self.takes_talon_half() || self.takes_whole_talon()
+    }
+
+    /// Whether the declarer tries to make at least 35⅔ points.
+    pub fn is_positive(&self) -> bool {
+        !self.is_negative()
+    }
+
+    /// Number of stiche the declarer has to make in the Bettler and Piccolo family, `None` for
+    /// all other games.
+    pub fn required_stiche(&self) -> Option<usize> {
+        match self {
+            GameType::B | GameType::BO => Some(0),
+            GameType::P1 | GameType::PB1 | GameType::PO1 => Some(1),
+            GameType::P2 | GameType::PB2 | GameType::PO2 => Some(2),
+            GameType::P3 | GameType::PB3 | GameType::PO3 => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Whether the declarer tries to make as few stiche as possible.
+    pub fn is_negative(&self) -> bool {
+        matches!(
+            self,
+            GameType::T
+                | GameType::P1
+                | GameType::P2
+                | GameType::P3
+                | GameType::PB1
+                | GameType::PB2
+                | GameType::PB3
+                | GameType::B
+                | GameType::PO1
+                | GameType::PO2
+                | GameType::PO3
+                | GameType::BO
+        )
+    }
+
+    /// Whether several players may play the game at the same time.
+    pub fn allows_multiple_declarers(&self) -> bool {
+        matches!(self, GameType::PB1 | GameType::PB2 | GameType::PB3)
+    }
+
+    /// Side the cards left in the talon count for at the end of the game.
+    ///
+    /// Cards the declarer laid down are part of their stiche and always count for them.
+    pub fn talon_owner(&self) -> TalonOwner {
+        match self {
+            GameType::S | GameType::D | GameType::PD => TalonOwner::Declarer,
+            GameType::R | GameType::BR | GameType::SR | GameType::SD | GameType::SPD => {
+                TalonOwner::Opponents
+            }
+            _ => TalonOwner::Nobody,
+        }
+    }
+}
+
+/// Side the remaining talon counts for, see [`GameType::talon_owner`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TalonOwner {
+    Declarer,
+    Opponents,
+    /// Negative games and Trischaken, where card points do not decide the game.
+    Nobody,
+}
+
 impl FromStr for GameType {
-    type Err = &'static str;
+    type Err = TafError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_uppercase().as_str() {
@@ -49,14 +195,60 @@ impl FromStr for GameType {
             "PD" => Ok(GameType::PD),
             "SD" => Ok(GameType::SD),
             "SPD" => Ok(GameType::SPD),
-            _ => Err("Invalid game type"),
+            _ => Err(TafError::InvalidGameType {
+                location: TafLocation::new(s, 0..s.len()),
+            }),
         }
     }
 }
 
+impl Display for GameType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::strategies::arb_game_type;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn game_type_display_round_trip(game_type in arb_game_type()) {
+            prop_assert_eq!(GameType::from_str(&game_type.to_string()), Ok(game_type));
+        }
+    }
+
+    #[test]
+    fn game_type_index() {
+        for (index, game_type) in GameType::ALL.iter().enumerate() {
+            assert_eq!(game_type.index(), index);
+        }
+    }
+
+    #[test]
+    fn game_type_properties() {
+        assert_eq!(GameType::T.value(), 1);
+        assert_eq!(GameType::R.value(), 1);
+        assert_eq!(GameType::S.value(), 2);
+        assert_eq!(GameType::P1.value(), 2);
+        assert_eq!(GameType::SR.value(), 2);
+        assert_eq!(GameType::B.value(), 3);
+        assert_eq!(GameType::D.value(), 4);
+
+        assert!(GameType::R.is_positive());
+        assert!(!GameType::T.is_positive());
+        assert!(GameType::S.takes_talon());
+        assert!(!GameType::SR.takes_talon());
+
+        assert_eq!(GameType::B.required_stiche(), Some(0));
+        assert_eq!(GameType::PB2.required_stiche(), Some(2));
+        assert_eq!(GameType::PO3.required_stiche(), Some(3));
+        assert_eq!(GameType::T.required_stiche(), None);
+        assert_eq!(GameType::R.required_stiche(), None);
+    }
 
     #[test]
     fn game_type_from_str() {
@@ -104,9 +296,21 @@ mod tests {
 
         assert_eq!(GameType::from_str("sPd"), Ok(GameType::SPD));
 
-        assert_eq!(GameType::from_str("invalid"), Err("Invalid game type"));
-        assert_eq!(GameType::from_str("psd"), Err("Invalid game type"));
-        assert_eq!(GameType::from_str("pb4"), Err("Invalid game type"));
-        assert_eq!(GameType::from_str("pb4"), Err("Invalid game type"));
+        assert!(matches!(
+            GameType::from_str("invalid"),
+            Err(TafError::InvalidGameType { .. })
+        ));
+        assert!(matches!(
+            GameType::from_str("psd"),
+            Err(TafError::InvalidGameType { .. })
+        ));
+        assert!(matches!(
+            GameType::from_str("pb4"),
+            Err(TafError::InvalidGameType { .. })
+        ));
+        assert!(matches!(
+            GameType::from_str("pb4"),
+            Err(TafError::InvalidGameType { .. })
+        ));
     }
 }
@@ -0,0 +1,178 @@
+//! Inference of which cards a hand cannot hold.
+//!
+//! The `excluded` set of every hand collects cards the player is known not to have. Cards are
+//! only removed from it when they move into the hand, so knowledge gained from earlier stiche is
+//! kept while the game advances.
+
+use super::card::{Card, Suit};
+use super::card_set::CardSet;
+use super::game::GameState;
+use super::stich::STICH_SIZE;
+
+impl GameState {
+    /// All known cards in the talon, the stiche, the current stich and the hands.
+    pub fn visible_cards(&self) -> CardSet {
+        let mut visible = self.talon[0].cards.union(&self.talon[1].cards);
+        for player in &self.players {
+            visible = visible
+                .union(&player.hand.cards)
+                .union(&player.stiche.cards);
+        }
+        visible.union(&self.stich.card_set()).known()
+    }
+
+    /// Cards the hand of `player` is known not to hold.
+    pub fn excluded(&self, player: usize) -> CardSet {
+        self.players[player].hand.excluded
+    }
+
+    /// Whether the hand of `player` may hold `card`.
+    pub fn can_hold(&self, player: usize, card: Card) -> bool {
+        self.players[player].hand.contains(card)
+            || (self.players[player].hand.cards.unknown_len() > 0
+                && !self.excluded(player).contains(card))
+    }
+
+    /// Known cards the hand of `player` may hold.
+    pub fn possible_cards(&self, player: usize) -> CardSet {
+        let hand = &self.players[player].hand;
+        if hand.cards.unknown_len() == 0 {
+            return hand.cards;
+        }
+        hand.cards.known().union(&hand.excluded.complement())
+    }
+
+    /// Adds everything that can be inferred from the current state to the excluded cards of every
+    /// hand.
+    ///
+    /// - Cards that are visible anywhere else cannot be in the hand.
+    /// - A hand without unknown cards holds nothing but its known cards.
+    /// - A player who did not follow the suit led in the current stich is void in that suit. A
+    ///   player who then did not play a Tarock either has no Tarock left (Tarockzwang).
+    pub fn update_exclusions(&mut self) {
+        let visible = self.visible_cards();
+        for player in self.players.iter_mut() {
+            let hand = &mut player.hand;
+            hand.excluded = hand.excluded.union(&visible);
+            if hand.cards.unknown_len() == 0 {
+                hand.excluded = hand.excluded.union(&hand.cards.complement());
+            }
+            // Cards that moved into the hand, e.g. from the talon, are no longer excluded
+            hand.excluded = hand.excluded.difference(&hand.cards);
+        }
+
+        let Some(led) = self.stich.first() else {
+            return;
+        };
+        for (position, played) in self.stich.iter().enumerate().skip(1) {
+            let player = (self.leader() + position) % STICH_SIZE;
+            let hand = &mut self.players[player].hand;
+            hand.excluded = hand.excluded.union(&voids(led, played));
+        }
+    }
+}
+
+/// Cards a player cannot hold after playing `played` onto a stich led with `led`.
+fn voids(led: Card, played: Card) -> CardSet {
+    let (Some(led_suit), Some(played_suit)) = (led.suit(), played.suit()) else {
+        return CardSet::new();
+    };
+    if played_suit == led_suit {
+        return CardSet::new();
+    }
+
+    let mut voids = CardSet::of_suit(led_suit);
+    if played_suit != Suit::Tarock {
+        voids = voids.union(&CardSet::of_suit(Suit::Tarock));
+    }
+    voids
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn voids_from_play() {
+        assert_eq!(voids(Card::HK, Card::H1), CardSet::new());
+        assert_eq!(voids(Card::T1, Card::T22), CardSet::new());
+        assert_eq!(voids(Card::HK, Card::T5), CardSet::of_suit(Suit::Herz));
+        assert_eq!(
+            voids(Card::HK, Card::X7),
+            CardSet::of_suit(Suit::Herz).union(&CardSet::of_suit(Suit::Tarock))
+        );
+        assert_eq!(voids(Card::T5, Card::X7), CardSet::of_suit(Suit::Tarock));
+        assert_eq!(voids(Card::UNKNOWN, Card::X7), CardSet::new());
+        assert_eq!(voids(Card::HK, Card::UNKNOWN), CardSet::new());
+    }
+
+    #[test]
+    fn update_exclusions_visible_cards() {
+        let mut game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - 1K/T// - R12",
+        )
+        .unwrap();
+        game_state.update_exclusions();
+
+        // Player 1 sees their whole hand
+        assert_eq!(
+            game_state.excluded(0),
+            game_state.players[0].hand.cards.complement()
+        );
+        assert!(game_state.can_hold(0, Card::HK));
+        assert!(!game_state.can_hold(0, Card::T22));
+
+        // Nobody else can hold cards of the talon or of player 1
+        for player in 1..4 {
+            assert!(game_state.excluded(player).contains(Card::XK));
+            assert!(game_state.excluded(player).contains(Card::H4));
+            assert!(game_state.excluded(player).contains(Card::T1));
+            assert!(!game_state.excluded(player).contains(Card::T22));
+            assert!(!game_state.can_hold(player, Card::HK));
+            assert!(game_state.can_hold(player, Card::T22));
+            assert_eq!(game_state.excluded(player).len(), 18);
+        }
+    }
+
+    #[test]
+    fn update_exclusions_from_stich() {
+        // Player 2 leads Herz, player 3 plays a Tarock and player 4 discards a Kreuz
+        let game_state = GameState::from_str(
+            "../...#........../#............/HKH1H2H3#.........../#.........../#H4T5X7 R1XK-1 2 /// - -",
+        )
+        .unwrap();
+
+        assert!(game_state.can_hold(2, Card::T22));
+        assert!(game_state.can_hold(3, Card::PK));
+        assert!(game_state.can_hold(2, Card::PK));
+        for card in CardSet::of_suit(Suit::Herz) {
+            assert!(!game_state.can_hold(2, card));
+            assert!(!game_state.can_hold(3, card));
+        }
+        for card in CardSet::of_suit(Suit::Tarock) {
+            assert!(!game_state.can_hold(3, card));
+        }
+        assert!(!game_state.can_hold(3, Card::X7));
+        assert!(game_state
+            .possible_cards(3)
+            .iter()
+            .all(|card| matches!(card.suit(), Some(Suit::Pick | Suit::Karo | Suit::Kreuz))));
+        assert!(game_state.can_hold(0, Card::HD));
+        assert!(!game_state.can_hold(0, Card::HK));
+    }
+
+    #[test]
+    fn update_exclusions_keeps_knowledge() {
+        let mut game_state = GameState::from_str(
+            "../...#........../#............/#.........../#.........../#H4T5X7 R1XK-1 2 /// - -",
+        )
+        .unwrap();
+        game_state.stich = Default::default();
+        game_state.update_exclusions();
+
+        assert!(!game_state.can_hold(3, Card::T22));
+        assert!(!game_state.can_hold(2, Card::HK));
+    }
+}
@@ -1,3 +1,24 @@
+pub mod ansage;
+pub mod bidding;
+pub mod bonus;
 pub mod card;
+pub mod card_set;
+pub mod counting;
+pub mod error;
 pub mod game;
 pub mod game_type;
+pub mod knowledge;
+pub mod moves;
+pub mod partner;
+pub mod pile;
+pub mod play_rules;
+pub mod rock_file;
+pub mod ruleset;
+pub mod settlement;
+pub mod spritzen;
+pub mod stich;
+pub mod talon;
+pub mod validation;
+
+#[cfg(test)]
+pub(crate) mod strategies;
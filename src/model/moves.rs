@@ -0,0 +1,1271 @@
+//! Spielzüge, moves of cards from one Kartenstapel to another.
+
+use std::fmt::Display;
+
+use super::bidding::Bidding;
+use super::card::Card;
+use super::card_set::CardSet;
+use super::error::MoveError;
+use super::game::{Calls, GameState, NUM_PLAYERS, TALON_HALF_SIZE};
+use super::game_type::{GameType, TalonOwner};
+use super::pile::Pile;
+use super::play_rules::trick_winner;
+use super::ruleset::Ruleset;
+use super::stich::Stich;
+use super::talon::TalonChoice;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Move {
+    /// `player` plays `card` into the current stich.
+    Play { player: usize, card: Card },
+    /// `player` takes the complete current stich onto their stiche.
+    TakeStich { player: usize },
+    /// `player` turns up the talon, showing the cards of both halves.
+    RevealTalon { player: usize, talon: [CardSet; 2] },
+    /// `player` takes talon half `half` into their hand.
+    TakeTalon { player: usize, half: usize },
+    /// `player` takes talon half `half` onto their stiche for the opponents of the declarer.
+    HandOverTalon { player: usize, half: usize },
+    /// `player` lays `cards` from their hand onto their stiche after taking the talon.
+    LayDown { player: usize, cards: CardSet },
+    /// `player` bids `typ` in the Lizitation.
+    Bid { player: usize, typ: GameType },
+    /// `player` passes in the Lizitation.
+    Pass { player: usize },
+}
+
+impl Move {
+    /// The player making the move.
+    pub fn player(&self) -> usize {
+        match *self {
+            Move::Play { player, .. }
+            | Move::TakeStich { player }
+            | Move::RevealTalon { player, .. }
+            | Move::TakeTalon { player, .. }
+            | Move::HandOverTalon { player, .. }
+            | Move::LayDown { player, .. }
+            | Move::Bid { player, .. }
+            | Move::Pass { player } => player,
+        }
+    }
+
+    /// The move leading from `before` to `after` under `rules`.
+    ///
+    /// Cards may only become known by moving out of the unknown cards of a hand, e.g. when a card
+    /// of a hidden hand is played, or by turning up the talon.
+    pub fn between(
+        before: &GameState,
+        after: &GameState,
+        rules: &Ruleset,
+    ) -> Result<Move, MoveError> {
+        if let Some(bidding) = before.bidding {
+            return Move::bidding_between(&bidding, before, after, rules);
+        }
+        if after.bidding.is_some() {
+            return Err(MoveError::GameChanged);
+        }
+
+        // Playing the called king reveals the teammate, which is checked once the move is known
+        let same_calls = before
+            .players
+            .iter()
+            .zip(&after.players)
+            .all(|(before, after)| {
+                Calls {
+                    teammate: None,
+                    ..before.calls
+                } == Calls {
+                    teammate: None,
+                    ..after.calls
+                }
+            });
+        if !same_calls || before.kleinen_stechen_großen != after.kleinen_stechen_großen {
+            return Err(MoveError::GameChanged);
+        }
+
+        let changed: Vec<Pile> = Pile::ALL
+            .into_iter()
+            .filter(|pile| match pile {
+                Pile::Stich => before.stich != after.stich,
+                pile => before.pile(*pile) != after.pile(*pile),
+            })
+            .collect();
+
+        let mv = match changed[..] {
+            [] if before.last_stich == after.last_stich => return Err(MoveError::NoMove),
+            [Pile::Hand(player), Pile::Stich] => {
+                let played = before.stich.len();
+                let extends = after.stich.len() == played + 1
+                    && before.stich.iter().eq(after.stich.iter().take(played));
+                let (true, Some(card)) = (extends, after.stich.get(played)) else {
+                    return Err(MoveError::CardsMismatch {
+                        from: Pile::Hand(player),
+                        to: Pile::Stich,
+                    });
+                };
+                transfer(before, after, Pile::Hand(player), Pile::Stich)?;
+                Move::Play { player, card }
+            }
+            [Pile::Stiche(player), Pile::Stich] => {
+                if !after.stich.is_empty() {
+                    return Err(MoveError::CardsMismatch {
+                        from: Pile::Stich,
+                        to: Pile::Stiche(player),
+                    });
+                }
+                transfer(before, after, Pile::Stich, Pile::Stiche(player))?;
+                Move::TakeStich { player }
+            }
+            [Pile::Talon(half), Pile::Hand(player)] => {
+                transfer(before, after, Pile::Talon(half), Pile::Hand(player))?;
+                let mv = Move::TakeTalon { player, half };
+                if !after.talon[half].cards.is_empty() {
+                    return Err(MoveError::Illegal {
+                        mv,
+                        reason: "the whole talon half has to be taken",
+                    });
+                }
+                mv
+            }
+            [Pile::Talon(_)] | [Pile::Talon(0), Pile::Talon(1)]
+                if before
+                    .talon
+                    .iter()
+                    .zip(&after.talon)
+                    .all(|(before, after)| before.cards.len() == after.cards.len()) =>
+            {
+                let Some(&player) = before.declarers().first() else {
+                    return Err(MoveError::NotOneMove { changed });
+                };
+                Move::RevealTalon {
+                    player,
+                    talon: after.talon.map(|half| half.cards),
+                }
+            }
+            [Pile::Talon(half), Pile::Stiche(player)] => {
+                transfer(before, after, Pile::Talon(half), Pile::Stiche(player))?;
+                let mv = Move::HandOverTalon { player, half };
+                if !after.talon[half].cards.is_empty() {
+                    return Err(MoveError::Illegal {
+                        mv,
+                        reason: "the whole talon half has to be taken",
+                    });
+                }
+                mv
+            }
+            [Pile::Hand(player), Pile::Stiche(other)] if player == other => {
+                let cards = transfer(before, after, Pile::Hand(player), Pile::Stiche(player))?;
+                Move::LayDown { player, cards }
+            }
+            _ => return Err(MoveError::NotOneMove { changed }),
+        };
+
+        let last_stich = match mv {
+            Move::TakeStich { player } => Some(player),
+            _ => before.last_stich,
+        };
+        if after.last_stich != last_stich {
+            return Err(MoveError::GameChanged);
+        }
+
+        let mut applied = *before;
+        applied.apply(&mv, rules)?;
+        if applied.players.map(|player| player.calls) != after.players.map(|player| player.calls) {
+            return Err(MoveError::GameChanged);
+        }
+        Ok(mv)
+    }
+
+    /// The bid or pass leading from `before` to `after` during the Lizitation `bidding`.
+    fn bidding_between(
+        bidding: &Bidding,
+        before: &GameState,
+        after: &GameState,
+        rules: &Ruleset,
+    ) -> Result<Move, MoveError> {
+        let player = bidding.turn;
+        let (passed, bid) = match after.bidding {
+            Some(after) => (after.passed[player], after.bids[player]),
+            None => {
+                let typ = after.players[player].calls.typ;
+                (typ.is_none(), typ)
+            }
+        };
+        let mv = match (passed, bid) {
+            (true, _) => Move::Pass { player },
+            (false, Some(typ)) if bid != bidding.bids[player] => Move::Bid { player, typ },
+            _ => return Err(MoveError::NoMove),
+        };
+
+        let mut expected = *before;
+        expected.apply(&mv, rules)?;
+        let same_calls = expected
+            .players
+            .iter()
+            .zip(&after.players)
+            .all(|(expected, after)| expected.calls == after.calls);
+        if !same_calls || expected.bidding != after.bidding {
+            return Err(MoveError::GameChanged);
+        }
+        let changed: Vec<Pile> = Pile::ALL
+            .into_iter()
+            .filter(|pile| match pile {
+                Pile::Stich => before.stich != after.stich,
+                pile => before.pile(*pile) != after.pile(*pile),
+            })
+            .collect();
+        if !changed.is_empty() || before.last_stich != after.last_stich {
+            return Err(MoveError::NotOneMove { changed });
+        }
+        Ok(mv)
+    }
+
+    /// Checks that the move can be made in `state` under `rules`.
+    pub fn check(&self, state: &GameState, rules: &Ruleset) -> Result<(), MoveError> {
+        let illegal = |reason| Err(MoveError::Illegal { mv: *self, reason });
+        match *self {
+            Move::Bid { player, typ } => {
+                let Some(bidding) = state.bidding else {
+                    return illegal("the Lizitation is over");
+                };
+                if !rules.allows(typ) {
+                    return illegal("the game is not played under the house rules");
+                }
+                if let Err(reason) = bidding.check_bid(player, typ) {
+                    return illegal(reason);
+                }
+                return Ok(());
+            }
+            Move::Pass { player } => {
+                let Some(bidding) = state.bidding else {
+                    return illegal("the Lizitation is over");
+                };
+                if let Err(reason) = bidding.check_pass(player) {
+                    return illegal(reason);
+                }
+                return Ok(());
+            }
+            _ if state.bidding.is_some() => return illegal("the Lizitation is not over"),
+            Move::Play { player, card } => {
+                if state.stich.is_full() {
+                    return illegal("the stich is already complete");
+                }
+                if player != state.current_player() {
+                    return illegal("it is not the player's turn");
+                }
+                if !state.can_hold(player, card) {
+                    return illegal("the player does not hold the card");
+                }
+                if card != Card::UNKNOWN && !state.legal_plays(player, rules).contains(card) {
+                    return illegal("the card does not follow suit or Tarock");
+                }
+            }
+            Move::TakeStich { player } => {
+                if !state.stich.is_full() {
+                    return illegal("the stich is not complete");
+                }
+                if trick_winner(&state.stich, state.leader(), state)
+                    .is_some_and(|winner| winner != player)
+                {
+                    return illegal("the player did not win the stich");
+                }
+            }
+            Move::RevealTalon { player, talon } => {
+                let calls = &state.players[player].calls;
+                if !calls
+                    .typ
+                    .is_some_and(|typ| typ.takes_talon_half() || typ.takes_whole_talon())
+                {
+                    return illegal("the player does not play a game that takes the talon");
+                }
+                if !state.before_first_stich() {
+                    return illegal("the talon can only be revealed before the first stich");
+                }
+                for (cards, revealed) in state.talon.iter().map(|half| half.cards).zip(talon) {
+                    if cards.len() != TALON_HALF_SIZE {
+                        return illegal("the talon has already been taken");
+                    }
+                    if revealed.len() != TALON_HALF_SIZE || revealed.unknown_len() > 0 {
+                        return illegal("the whole talon has to be revealed");
+                    }
+                    if !cards.known().is_subset(&revealed)
+                        || !revealed
+                            .difference(&cards)
+                            .is_disjoint(&state.visible_cards())
+                    {
+                        return illegal("the talon holds other cards");
+                    }
+                }
+                if !talon[0].is_disjoint(&talon[1]) {
+                    return illegal("the talon holds other cards");
+                }
+            }
+            Move::TakeTalon { player, half } => {
+                let calls = &state.players[player].calls;
+                let Some(typ) = calls
+                    .typ
+                    .filter(|typ| typ.takes_talon_half() || typ.takes_whole_talon())
+                else {
+                    return illegal("the player does not play a game that takes the talon");
+                };
+                if !state.before_first_stich() {
+                    return illegal("the talon can only be taken before the first stich");
+                }
+                if state.talon[half].cards.is_empty() {
+                    return illegal("the talon half is empty");
+                }
+                if calls
+                    .taken_talon
+                    .half()
+                    .is_some_and(|chosen| chosen != half)
+                {
+                    return illegal("the other talon half was chosen");
+                }
+                if typ.takes_talon_half()
+                    && (state.cards_to_lay_down(player) > 0
+                        || !state.players[player].stiche.cards.is_empty())
+                {
+                    return illegal("only one talon half can be taken");
+                }
+            }
+            Move::HandOverTalon { player, half } => {
+                if state.players[player].calls.typ.is_some() {
+                    return illegal("the talon can only be handed over to an opponent");
+                }
+                if !state.before_first_stich() {
+                    return illegal("the talon can only be handed over before the first stich");
+                }
+                if state.talon[half].cards.is_empty() {
+                    return illegal("the talon half is empty");
+                }
+                let Some(typ) = state
+                    .game_type()
+                    .filter(|typ| typ.talon_owner() == TalonOwner::Opponents)
+                else {
+                    return illegal("the talon does not go to the opponents");
+                };
+                if typ.takes_talon_half() && state.talon.iter().all(|half| !half.cards.is_empty()) {
+                    return illegal("the declarer has not chosen a talon half yet");
+                }
+            }
+            Move::LayDown { player, cards } => {
+                let calls = &state.players[player].calls;
+                if !calls
+                    .typ
+                    .is_some_and(|typ| typ.takes_talon_half() || typ.takes_whole_talon())
+                {
+                    return illegal("the player does not play a game that takes the talon");
+                }
+                if !state.before_first_stich() {
+                    return illegal("cards can only be laid down before the first stich");
+                }
+                let hand = &state.players[player].hand.cards;
+                if cards.len() > hand.len()
+                    || !cards.iter().all(|card| state.can_hold(player, card))
+                {
+                    return illegal("the player does not hold the cards");
+                }
+                if let Err(reason) = state.check_lay_down(player, cards) {
+                    return illegal(reason);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What [`GameState::apply`] changed, needed to take a move back with [`GameState::undo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Undo {
+    mv: Move,
+    /// The cards that were moved.
+    moved: CardSet,
+    /// Moved cards that were taken from the unknown cards of a hand.
+    revealed: CardSet,
+    stich: Stich,
+    last_stich: Option<usize>,
+    excluded: [CardSet; NUM_PLAYERS],
+    bidding: Option<Bidding>,
+    calls: [Calls; NUM_PLAYERS],
+    talon: [CardSet; 2],
+}
+
+impl Undo {
+    /// The move that was applied.
+    pub fn mv(&self) -> Move {
+        self.mv
+    }
+}
+
+impl GameState {
+    /// Makes `mv` under `rules`, moving its cards from one pile to another.
+    ///
+    /// Known cards a hand does not hold as known cards are taken from its unknown cards. The
+    /// excluded cards of all hands are updated afterwards. Fails without changing the state if
+    /// the move is not allowed.
+    pub fn apply(&mut self, mv: &Move, rules: &Ruleset) -> Result<Undo, MoveError> {
+        mv.check(self, rules)?;
+
+        let mut undo = Undo {
+            mv: *mv,
+            moved: CardSet::new(),
+            revealed: CardSet::new(),
+            stich: self.stich,
+            last_stich: self.last_stich,
+            excluded: self.players.map(|player| player.hand.excluded),
+            bidding: self.bidding,
+            calls: self.players.map(|player| player.calls),
+            talon: self.talon.map(|half| half.cards),
+        };
+        match *mv {
+            Move::Play { player, card } => {
+                undo.moved.insert(card);
+                undo.revealed = take(&mut self.players[player].hand.cards, undo.moved);
+                self.stich.push(card);
+                self.reveal_teammate(player, card);
+            }
+            Move::TakeStich { player } => {
+                undo.moved = self.stich.card_set();
+                self.players[player]
+                    .stiche
+                    .cards
+                    .extend(all_cards(undo.moved));
+                self.stich = Stich::new();
+                self.last_stich = Some(player);
+            }
+            Move::RevealTalon { talon, .. } => {
+                for (half, cards) in self.talon.iter_mut().zip(talon) {
+                    half.cards = cards;
+                }
+            }
+            Move::TakeTalon { player, half } => {
+                undo.moved = std::mem::take(&mut self.talon[half].cards);
+                self.players[player]
+                    .hand
+                    .cards
+                    .extend(all_cards(undo.moved));
+            }
+            Move::HandOverTalon { player, half } => {
+                undo.moved = std::mem::take(&mut self.talon[half].cards);
+                self.players[player]
+                    .stiche
+                    .cards
+                    .extend(all_cards(undo.moved));
+            }
+            Move::LayDown { player, cards } => {
+                undo.moved = cards;
+                undo.revealed = take(&mut self.players[player].hand.cards, cards);
+                self.players[player].stiche.cards.extend(all_cards(cards));
+            }
+            Move::Bid { player, typ } => self.bid(|bidding| bidding.bid(player, typ)),
+            Move::Pass { player } => self.bid(|bidding| bidding.pass(player)),
+        }
+        self.update_exclusions();
+        Ok(undo)
+    }
+
+    /// Takes back the move [`GameState::apply`] returned `undo` for.
+    ///
+    /// The state has to be the one `apply` left behind.
+    pub fn undo(&mut self, undo: &Undo) {
+        match undo.mv {
+            Move::Play { player, .. } => {
+                give(
+                    &mut self.players[player].hand.cards,
+                    undo.moved,
+                    undo.revealed,
+                );
+            }
+            Move::TakeStich { player } => {
+                take(&mut self.players[player].stiche.cards, undo.moved);
+            }
+            Move::RevealTalon { .. } => {}
+            Move::TakeTalon { player, .. } => {
+                take(&mut self.players[player].hand.cards, undo.moved);
+            }
+            Move::HandOverTalon { player, .. } => {
+                take(&mut self.players[player].stiche.cards, undo.moved);
+            }
+            Move::LayDown { player, .. } => {
+                take(&mut self.players[player].stiche.cards, undo.moved);
+                give(
+                    &mut self.players[player].hand.cards,
+                    undo.moved,
+                    undo.revealed,
+                );
+            }
+            Move::Bid { .. } | Move::Pass { .. } => {}
+        }
+        for (player, calls) in self.players.iter_mut().zip(undo.calls) {
+            player.calls = calls;
+        }
+        for (half, cards) in self.talon.iter_mut().zip(undo.talon) {
+            half.cards = cards;
+        }
+        self.bidding = undo.bidding;
+        self.stich = undo.stich;
+        self.last_stich = undo.last_stich;
+        for (player, excluded) in self.players.iter_mut().zip(undo.excluded) {
+            player.hand.excluded = excluded;
+        }
+    }
+}
+
+impl GameState {
+    /// Makes a bid or pass with `bid` and declares the game once the Lizitation is over.
+    ///
+    /// The move has been checked already, so `bid` cannot fail.
+    fn bid(&mut self, bid: impl FnOnce(&mut Bidding) -> Result<(), &'static str>) {
+        let Some(bidding) = self.bidding.as_mut() else {
+            return;
+        };
+        bid(bidding).expect("checked bid");
+        let Some(declarers) = bidding.declarers() else {
+            return;
+        };
+        for declarer in declarers {
+            let Some(typ) = bidding.bids[declarer] else {
+                continue;
+            };
+            let calls = &mut self.players[declarer].calls;
+            calls.typ = Some(typ);
+            calls.taken_talon = TalonChoice::untaken(typ);
+        }
+        self.bidding = None;
+    }
+
+    /// Notes `player` as teammate of every declarer who called `card`.
+    fn reveal_teammate(&mut self, player: usize, card: Card) {
+        for calls in self.players.iter_mut().map(|player| &mut player.calls) {
+            if calls.typ.is_some() && calls.called_king == Some(card) && calls.teammate.is_none() {
+                calls.teammate = Some(player);
+            }
+        }
+    }
+}
+
+/// Known and unknown cards of `cards`.
+fn all_cards(cards: CardSet) -> impl Iterator<Item = Card> {
+    cards
+        .iter()
+        .chain(std::iter::repeat_n(Card::UNKNOWN, cards.unknown_len()))
+}
+
+/// Removes `cards` from `pile`, taking known cards that `pile` does not hold from its unknown
+/// cards. Returns the cards taken that way.
+fn take(pile: &mut CardSet, cards: CardSet) -> CardSet {
+    let mut revealed = CardSet::new();
+    for card in all_cards(cards) {
+        if !pile.remove(card) {
+            pile.remove(Card::UNKNOWN);
+            revealed.insert(card);
+        }
+    }
+    revealed
+}
+
+/// Adds `cards` to `pile`, turning the `revealed` ones back into unknown cards.
+fn give(pile: &mut CardSet, cards: CardSet, revealed: CardSet) {
+    for card in all_cards(cards) {
+        if card != Card::UNKNOWN && revealed.contains(card) {
+            pile.insert(Card::UNKNOWN);
+        } else {
+            pile.insert(card);
+        }
+    }
+}
+
+/// Checks that the cards leaving `from` are the ones arriving in `to` and returns them as they
+/// arrived.
+///
+/// Unknown cards leaving `from` may arrive as known cards that were not visible before.
+fn transfer(
+    before: &GameState,
+    after: &GameState,
+    from: Pile,
+    to: Pile,
+) -> Result<CardSet, MoveError> {
+    let mismatch = Err(MoveError::CardsMismatch { from, to });
+    let (before_from, after_from) = (before.pile(from), after.pile(from));
+    let (before_to, after_to) = (before.pile(to), after.pile(to));
+    if !after_from.is_subset(&before_from) || !before_to.is_subset(&after_to) {
+        return mismatch;
+    }
+
+    let lost = before_from.known().difference(&after_from);
+    let lost_unknown = before_from.unknown_len() - after_from.unknown_len();
+    let gained = after_to.known().difference(&before_to);
+    let gained_unknown = after_to.unknown_len() - before_to.unknown_len();
+
+    let revealed = gained.difference(&lost);
+    if !lost.is_subset(&gained)
+        || revealed.len() + gained_unknown != lost_unknown
+        || !revealed.is_disjoint(&before.visible_cards())
+    {
+        return mismatch;
+    }
+
+    let mut moved = gained;
+    moved.extend(std::iter::repeat_n(Card::UNKNOWN, gained_unknown));
+    Ok(moved)
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Move::Play { player, card } => write!(f, "player {} plays {card}", player + 1),
+            Move::TakeStich { player } => write!(f, "player {} takes the stich", player + 1),
+            Move::RevealTalon { player, talon } => write!(
+                f,
+                "player {} turns up the talon {}/{}",
+                player + 1,
+                talon[0],
+                talon[1]
+            ),
+            Move::TakeTalon { player, half } => {
+                write!(f, "player {} takes the {}", player + 1, Pile::Talon(*half))
+            }
+            Move::HandOverTalon { player, half } => write!(
+                f,
+                "player {} takes the {} for the opponents",
+                player + 1,
+                Pile::Talon(*half)
+            ),
+            Move::LayDown { player, cards } => {
+                write!(f, "player {} lays down {cards}", player + 1)
+            }
+            Move::Bid { player, typ } => write!(f, "player {} bids {typ}", player + 1),
+            Move::Pass { player } => write!(f, "player {} passes", player + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn state(taf: &str) -> GameState {
+        GameState::from_str(taf).unwrap()
+    }
+
+    fn between(before: &str, after: &str) -> Result<Move, MoveError> {
+        Move::between(&state(before), &state(after), &Ruleset::default())
+    }
+
+    const START: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - /// - -";
+    const TALON_TAKEN: &str = "/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKPXKX10X9/#............/#............/#............/# R1XK-1 - /// - -";
+    const LAID_DOWN: &str = "/H4H3H2#HKT1T3T5T6K1K2K3K4KBKPXK/HDX10X9#............/#............/#............/# R1XK-1 - /// - -";
+    const LED: &str = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#............/#............/#............/#HK R1XK-1 - /// - -";
+    const FOLLOWED: &str = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#.........../#............/#............/#HKH1 R1XK-1 - /// - -";
+    const COMPLETE: &str = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#.........../#.........../#.........../#HKH1HBT22 R1XK-1 - /// - -";
+    const TAKEN: &str = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#.........../#.........../#.........../HKH1HBT22# R1XK-1 4 /// - -";
+
+    #[test]
+    fn move_between() {
+        assert_eq!(
+            between(START, TALON_TAKEN),
+            Ok(Move::TakeTalon { player: 0, half: 0 })
+        );
+        assert_eq!(
+            between(TALON_TAKEN, LAID_DOWN),
+            Ok(Move::LayDown {
+                player: 0,
+                cards: CardSet::from_iter([Card::HD, Card::X10, Card::X9]),
+            })
+        );
+        assert_eq!(
+            between(LAID_DOWN, LED),
+            Ok(Move::Play {
+                player: 0,
+                card: Card::HK
+            })
+        );
+        assert_eq!(
+            between(LED, FOLLOWED),
+            Ok(Move::Play {
+                player: 1,
+                card: Card::H1
+            })
+        );
+        assert_eq!(between(COMPLETE, TAKEN), Ok(Move::TakeStich { player: 3 }));
+    }
+
+    #[test]
+    fn move_between_unknown() {
+        // Seen by an opponent, the laid down cards stay hidden
+        assert_eq!(
+            between(
+                "/H4H3H2#.............../#............/#............/#............/# R1XK-1 - /// - -",
+                "/H4H3H2#............/...#............/#............/#............/# R1XK-1 - /// - -",
+            ),
+            Ok(Move::LayDown {
+                player: 0,
+                cards: CardSet::from_iter([Card::UNKNOWN; 3]),
+            })
+        );
+    }
+
+    #[test]
+    fn move_between_errors() {
+        assert_eq!(between(START, START), Err(MoveError::NoMove));
+        assert_eq!(
+            between(START, LAID_DOWN),
+            Err(MoveError::NotOneMove {
+                changed: vec![Pile::Talon(0), Pile::Hand(0), Pile::Stiche(0)],
+            })
+        );
+        assert_eq!(
+            between(START, &START.replace("R1XK-1", "R1XK-2")),
+            Err(MoveError::GameChanged)
+        );
+        assert_eq!(
+            between(COMPLETE, &TAKEN.replace(" 4 ", " - ")),
+            Err(MoveError::GameChanged)
+        );
+
+        // H4 lies in the lower talon
+        assert_eq!(
+            between(
+                LED,
+                "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#.........../#............/#............/#HKH4 R1XK-1 - /// - -",
+            ),
+            Err(MoveError::CardsMismatch {
+                from: Pile::Hand(1),
+                to: Pile::Stich,
+            })
+        );
+
+        // Player 3 plays before player 2
+        assert_eq!(
+            between(
+                LED,
+                "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#............/#.........../#............/#HKH1 R1XK-1 - /// - -",
+            ),
+            Err(MoveError::Illegal {
+                mv: Move::Play {
+                    player: 2,
+                    card: Card::H1
+                },
+                reason: "it is not the player's turn",
+            })
+        );
+
+        // The declarer chose the lower talon
+        assert!(matches!(
+            between(
+                &START.replace("R1XK-1", "R1XK-2"),
+                &TALON_TAKEN.replace("R1XK-1", "R1XK-2"),
+            ),
+            Err(MoveError::Illegal {
+                reason: "the other talon half was chosen",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn game_state_apply_undo() {
+        let history = [START, TALON_TAKEN, LAID_DOWN, LED, FOLLOWED];
+        let mut game_state = state(START);
+        let mut undos = Vec::new();
+        for (before, after) in history.iter().zip(&history[1..]) {
+            let mv = between(before, after).unwrap();
+            undos.push(game_state.apply(&mv, &Ruleset::default()).unwrap());
+            assert_eq!(game_state, state(after), "after {mv}");
+        }
+        for (undo, before) in undos.iter().rev().zip(history.iter().rev().skip(1)) {
+            game_state.undo(undo);
+            assert_eq!(game_state, state(before), "undoing {}", undo.mv());
+        }
+
+        let mut game_state = state(COMPLETE);
+        let undo = game_state
+            .apply(&Move::TakeStich { player: 3 }, &Ruleset::default())
+            .unwrap();
+        // The voids shown in the stich are kept, TAF cannot express them
+        assert_eq!(game_state.to_string(), state(TAKEN).to_string());
+        assert!(game_state.excluded(3).contains(Card::HP));
+        assert!(!state(TAKEN).excluded(3).contains(Card::HP));
+        game_state.undo(&undo);
+        assert_eq!(game_state, state(COMPLETE));
+    }
+
+    #[test]
+    fn talon_exchange() {
+        let hidden = ".../...#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - /// - -";
+        let handed_over = "/#HKT1T3T5T6K1K2K3K4KBKPXK/HDX10X9#............/H4H3H2#............/#............/# R1XK-1 - /// - -";
+        let history = [hidden, START, TALON_TAKEN, LAID_DOWN, handed_over];
+        assert_eq!(
+            between(hidden, START),
+            Ok(Move::RevealTalon {
+                player: 0,
+                talon: [
+                    CardSet::from_iter([Card::XK, Card::X10, Card::X9]),
+                    CardSet::from_iter([Card::H4, Card::H3, Card::H2]),
+                ],
+            })
+        );
+        assert_eq!(
+            between(LAID_DOWN, handed_over),
+            Ok(Move::HandOverTalon { player: 1, half: 1 })
+        );
+
+        let mut game_state = state(hidden);
+        let mut undos = Vec::new();
+        for (before, after) in history.iter().zip(&history[1..]) {
+            let mv = between(before, after).unwrap();
+            undos.push(game_state.apply(&mv, &Ruleset::default()).unwrap());
+            assert_eq!(
+                game_state.to_string(),
+                state(after).to_string(),
+                "after {mv}"
+            );
+        }
+        for (undo, before) in undos.iter().rev().zip(history.iter().rev().skip(1)) {
+            game_state.undo(undo);
+            assert_eq!(
+                game_state.to_string(),
+                state(before).to_string(),
+                "undoing {}",
+                undo.mv()
+            );
+        }
+    }
+
+    #[test]
+    fn talon_exchange_errors() {
+        let illegal = |taf: &str, mv: Move| match state(taf).apply(&mv, &Ruleset::default()) {
+            Err(MoveError::Illegal { reason, .. }) => reason,
+            result => panic!("expected {mv} to be illegal, got {result:?}"),
+        };
+        assert_eq!(
+            illegal(
+                START,
+                Move::RevealTalon {
+                    player: 0,
+                    talon: [
+                        CardSet::from_iter([Card::HK, Card::X10, Card::X9]),
+                        CardSet::from_iter([Card::H4, Card::H3, Card::H2]),
+                    ],
+                }
+            ),
+            "the talon holds other cards"
+        );
+        assert_eq!(
+            illegal(
+                &TALON_TAKEN.replace("R1XK-1", "R1XK--"),
+                Move::TakeTalon { player: 0, half: 1 }
+            ),
+            "only one talon half can be taken"
+        );
+        assert_eq!(
+            illegal(START, Move::HandOverTalon { player: 1, half: 1 }),
+            "the declarer has not chosen a talon half yet"
+        );
+        assert_eq!(
+            illegal(LAID_DOWN, Move::HandOverTalon { player: 0, half: 1 }),
+            "the talon can only be handed over to an opponent"
+        );
+        assert_eq!(
+            illegal(
+                &LAID_DOWN.replace("R1XK-1", "D1--1"),
+                Move::HandOverTalon { player: 1, half: 1 }
+            ),
+            "the talon does not go to the opponents"
+        );
+        assert_eq!(
+            illegal(
+                TALON_TAKEN,
+                Move::LayDown {
+                    player: 0,
+                    cards: CardSet::from_iter([Card::HK, Card::X10, Card::X9]),
+                }
+            ),
+            "kings and Trullstücke cannot be laid down"
+        );
+
+        // In a Solo the talon goes to the opponents untouched
+        let solo = START.replace("R1XK-1", "SR1XK--");
+        assert_eq!(
+            illegal(&solo, Move::TakeTalon { player: 0, half: 0 }),
+            "the player does not play a game that takes the talon"
+        );
+        let mut game_state = state(&solo);
+        for half in 0..2 {
+            game_state
+                .apply(
+                    &Move::HandOverTalon { player: 2, half },
+                    &Ruleset::default(),
+                )
+                .unwrap();
+        }
+        assert_eq!(game_state.players[2].stiche.cards.len(), 6);
+    }
+
+    #[test]
+    fn sechserbock() {
+        let mut game_state = state(&START.replace("R1XK-1", "S1--12"));
+        game_state
+            .apply(&Move::TakeTalon { player: 0, half: 0 }, &Ruleset::default())
+            .unwrap();
+        let lay_down = Move::LayDown {
+            player: 0,
+            cards: CardSet::from_iter([Card::HD, Card::X10, Card::X9]),
+        };
+        assert_eq!(
+            game_state.apply(&lay_down, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv: lay_down,
+                reason: "the whole talon has to be taken first"
+            })
+        );
+
+        game_state
+            .apply(&Move::TakeTalon { player: 0, half: 1 }, &Ruleset::default())
+            .unwrap();
+        assert_eq!(game_state.cards_to_lay_down(0), 6);
+        game_state
+            .apply(
+                &Move::LayDown {
+                    player: 0,
+                    cards: CardSet::from_iter([
+                        Card::HD,
+                        Card::H4,
+                        Card::H3,
+                        Card::H2,
+                        Card::X10,
+                        Card::X9,
+                    ]),
+                },
+                &Ruleset::default(),
+            )
+            .unwrap();
+        assert_eq!(game_state.cards_to_lay_down(0), 0);
+        assert_eq!(game_state.validate(), vec![]);
+    }
+
+    #[test]
+    fn called_king_reveals_teammate() {
+        let before = LED.replace("R1XK-1", "R1PK-1");
+        let after = FOLLOWED.replace("#HKH1 R1XK-1", "#HKPK R1PK21");
+        let mv = Move::Play {
+            player: 1,
+            card: Card::PK,
+        };
+        assert_eq!(between(&before, &after), Ok(mv));
+        assert_eq!(
+            between(&before, &after.replace("R1PK21", "R1PK-1")),
+            Err(MoveError::GameChanged)
+        );
+
+        let mut game_state = state(&before);
+        assert!(!game_state.partner_revealed(0));
+        let undo = game_state.apply(&mv, &Ruleset::default()).unwrap();
+        assert_eq!(game_state.to_string(), state(&after).to_string());
+        assert_eq!(game_state.players[0].calls.teammate, Some(1));
+        game_state.undo(&undo);
+        assert_eq!(game_state, state(&before));
+    }
+
+    #[test]
+    fn game_state_apply_knowledge() {
+        let mut game_state = state(FOLLOWED);
+        let undo = game_state
+            .apply(
+                &Move::Play {
+                    player: 2,
+                    card: Card::T22,
+                },
+                &Ruleset::default(),
+            )
+            .unwrap();
+        // Player 3 did not follow Herz and T22 can no longer be held by anyone
+        assert!(!game_state.can_hold(2, Card::HB));
+        assert!(game_state.can_hold(2, Card::T21));
+        assert!(!game_state.can_hold(3, Card::T22));
+
+        game_state.undo(&undo);
+        assert_eq!(game_state, state(FOLLOWED));
+        assert!(game_state.can_hold(2, Card::HB));
+    }
+
+    #[test]
+    fn game_state_apply_errors() {
+        let mut game_state = state(LED);
+        let mv = Move::Play {
+            player: 2,
+            card: Card::H1,
+        };
+        assert_eq!(
+            game_state.apply(&mv, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv,
+                reason: "it is not the player's turn"
+            })
+        );
+        let mv = Move::Play {
+            player: 1,
+            card: Card::H4,
+        };
+        assert!(game_state.apply(&mv, &Ruleset::default()).is_err());
+        assert!(game_state
+            .apply(&Move::TakeStich { player: 0 }, &Ruleset::default())
+            .is_err());
+        assert_eq!(
+            state(COMPLETE).apply(&Move::TakeStich { player: 0 }, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv: Move::TakeStich { player: 0 },
+                reason: "the player did not win the stich"
+            })
+        );
+        assert!(game_state
+            .apply(&Move::TakeTalon { player: 0, half: 1 }, &Ruleset::default())
+            .is_err());
+
+        assert_eq!(game_state, state(LED));
+
+        // Player 2 may be void in Herz, unless they are known to hold HB
+        let mv = Move::Play {
+            player: 1,
+            card: Card::T22,
+        };
+        assert!(game_state.apply(&mv, &Ruleset::default()).is_ok());
+        let followed = "/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HDX10X9#HB.........../#............/#............/#HK R1XK-1 - /// - -";
+        let mut game_state = state(followed);
+        assert_eq!(
+            game_state.apply(&mv, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv,
+                reason: "the card does not follow suit or Tarock"
+            })
+        );
+        assert_eq!(game_state, state(followed));
+    }
+
+    #[test]
+    fn game_state_apply_ruleset() {
+        // In a Bettler player 2 follows the Herz Bube with a Tarock
+        let taf = "XKX10X9/H3#HKH2T2T3T5T6K1K2K3K4KP/#X7T1T4/#............/#............/#HB B1--- - /// - -";
+        let mv = Move::Play {
+            player: 1,
+            card: Card::T1,
+        };
+        // The Wiener rules keep the Pagat back while another Tarock is left
+        assert_eq!(
+            state(taf).apply(&mv, &Ruleset::wiener()),
+            Err(MoveError::Illegal {
+                mv,
+                reason: "the card does not follow suit or Tarock"
+            })
+        );
+        assert!(state(taf).apply(&mv, &Ruleset::grazer()).is_ok());
+        assert_eq!(
+            state(taf).legal_plays(1, &Ruleset::grazer()),
+            [Card::T1, Card::T4].into_iter().collect()
+        );
+    }
+
+    const LIZITATION: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# L1:-/-/-/- - /// - -";
+    const MEIN_SPIEL: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# L2:R/-/-/- - /// - -";
+    const RAISED: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# L3:R/SR/-/- - /// - -";
+    const PASSED: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# L4:R/SR/x/- - /// - -";
+    const PASSED_AGAIN: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# L1:R/SR/x/x - /// - -";
+    const DECLARED: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# SR2--- - /// - -";
+
+    #[test]
+    fn lizitation_between_apply_undo() {
+        let history = [
+            LIZITATION,
+            MEIN_SPIEL,
+            RAISED,
+            PASSED,
+            PASSED_AGAIN,
+            DECLARED,
+        ];
+        let moves: Vec<Move> = history
+            .iter()
+            .zip(&history[1..])
+            .map(|(before, after)| between(before, after).unwrap())
+            .collect();
+        assert_eq!(
+            moves,
+            vec![
+                Move::Bid {
+                    player: 0,
+                    typ: GameType::R
+                },
+                Move::Bid {
+                    player: 1,
+                    typ: GameType::SR
+                },
+                Move::Pass { player: 2 },
+                Move::Pass { player: 3 },
+                Move::Pass { player: 0 },
+            ]
+        );
+
+        let mut game_state = state(LIZITATION);
+        let mut undos = Vec::new();
+        for (mv, after) in moves.iter().zip(&history[1..]) {
+            undos.push(game_state.apply(mv, &Ruleset::default()).unwrap());
+            assert_eq!(game_state, state(after), "after {mv}");
+        }
+        assert_eq!(game_state.game_type(), Some(GameType::SR));
+        for (undo, before) in undos.iter().rev().zip(history.iter().rev().skip(1)) {
+            game_state.undo(undo);
+            assert_eq!(game_state, state(before), "undoing {}", undo.mv());
+        }
+    }
+
+    #[test]
+    fn lizitation_bei() {
+        // Player 1 bids Piccolo bei and player 2 joins with Driccolo bei
+        let cards =
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/#";
+        let history = [
+            "L1:-/-/-/-",
+            "L2:PB1/-/-/-",
+            "L3:PB1/PB3/-/-",
+            "L4:PB1/PB3/x/-",
+            "PB11PB32---",
+        ]
+        .map(|game| format!("{cards} {game} - /// - -"));
+        let moves = [
+            Move::Bid {
+                player: 0,
+                typ: GameType::PB1,
+            },
+            Move::Bid {
+                player: 1,
+                typ: GameType::PB3,
+            },
+            Move::Pass { player: 2 },
+            Move::Pass { player: 3 },
+        ];
+
+        let mut game_state = state(&history[0]);
+        for (mv, (before, after)) in moves.iter().zip(history.iter().zip(&history[1..])) {
+            assert_eq!(between(before, after), Ok(*mv));
+            game_state.apply(mv, &Ruleset::default()).unwrap();
+            assert_eq!(game_state, state(after), "after {mv}");
+        }
+        assert_eq!(game_state.players[0].calls.typ, Some(GameType::PB1));
+        assert_eq!(game_state.players[1].calls.typ, Some(GameType::PB3));
+        assert_eq!(game_state.declarers(), vec![0, 1]);
+        assert_eq!(game_state.validate(), vec![]);
+    }
+
+    #[test]
+    fn lizitation_errors() {
+        let bid = |player, typ| Move::Bid { player, typ };
+        assert_eq!(
+            between(MEIN_SPIEL, &MEIN_SPIEL.replace("L2:R/-", "L3:R/R")),
+            Err(MoveError::Illegal {
+                mv: bid(1, GameType::R),
+                reason: "the bid is not higher than the highest bid"
+            })
+        );
+        assert_eq!(
+            between(MEIN_SPIEL, &MEIN_SPIEL.replace("L2:R/-/-", "L3:R/-/SR")),
+            Err(MoveError::NoMove)
+        );
+        assert_eq!(
+            between(MEIN_SPIEL, &RAISED.replace("L3", "L4")),
+            Err(MoveError::GameChanged)
+        );
+        assert!(matches!(
+            between(MEIN_SPIEL, &RAISED.replace("#HKHD", "#HD")),
+            Err(MoveError::NotOneMove { .. })
+        ));
+        assert_eq!(between(LIZITATION, START), Err(MoveError::GameChanged));
+        assert_eq!(
+            state(LIZITATION).apply(&Move::Pass { player: 0 }, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv: Move::Pass { player: 0 },
+                reason: "Vorhand has to open the Lizitation"
+            })
+        );
+        assert_eq!(
+            state(START).apply(&Move::Pass { player: 0 }, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv: Move::Pass { player: 0 },
+                reason: "the Lizitation is over"
+            })
+        );
+        // The Grazer rules do not play the Sechserbock
+        assert!(state(MEIN_SPIEL)
+            .apply(&bid(1, GameType::S), &Ruleset::wiener())
+            .is_ok());
+        assert_eq!(
+            state(MEIN_SPIEL).apply(&bid(1, GameType::S), &Ruleset::grazer()),
+            Err(MoveError::Illegal {
+                mv: bid(1, GameType::S),
+                reason: "the game is not played under the house rules"
+            })
+        );
+        let mv = Move::Play {
+            player: 0,
+            card: Card::HK,
+        };
+        assert_eq!(
+            state(MEIN_SPIEL).apply(&mv, &Ruleset::default()),
+            Err(MoveError::Illegal {
+                mv,
+                reason: "the Lizitation is not over"
+            })
+        );
+    }
+
+    #[test]
+    fn move_display() {
+        assert_eq!(
+            Move::Play {
+                player: 2,
+                card: Card::T22
+            }
+            .to_string(),
+            "player 3 plays T22"
+        );
+        assert_eq!(
+            Move::TakeStich { player: 0 }.to_string(),
+            "player 1 takes the stich"
+        );
+        assert_eq!(
+            Move::TakeTalon { player: 0, half: 1 }.to_string(),
+            "player 1 takes the lower talon"
+        );
+        assert_eq!(
+            Move::HandOverTalon { player: 1, half: 0 }.to_string(),
+            "player 2 takes the upper talon for the opponents"
+        );
+        assert_eq!(
+            Move::RevealTalon {
+                player: 0,
+                talon: [
+                    CardSet::from_iter([Card::XK, Card::X10, Card::X9]),
+                    CardSet::from_iter([Card::H4, Card::H3, Card::H2]),
+                ],
+            }
+            .to_string(),
+            "player 1 turns up the talon XKX10X9/H2H3H4"
+        );
+        assert_eq!(
+            Move::LayDown {
+                player: 0,
+                cards: CardSet::from_iter([Card::HK, Card::HD])
+            }
+            .to_string(),
+            "player 1 lays down HKHD"
+        );
+        assert_eq!(
+            Move::Bid {
+                player: 0,
+                typ: GameType::R
+            }
+            .to_string(),
+            "player 1 bids R"
+        );
+        assert_eq!(Move::Pass { player: 3 }.to_string(), "player 4 passes");
+    }
+}
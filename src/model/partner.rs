@@ -0,0 +1,107 @@
+//! The partner of the declarer in games that call a king.
+//!
+//! In a Rufer the player holding the called king plays with the declarer, but nobody knows who
+//! that is until the king is played. If the king lies in the talon or in the declarer's own hand,
+//! the declarer plays alone against the other three.
+
+use super::game::{GameState, NUM_PLAYERS};
+
+/// Who plays with a declarer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Partner {
+    /// The player holding the called king.
+    Player(usize),
+    /// Nobody, either no king is called or the declarer holds it or it lies in the talon.
+    Alone,
+}
+
+impl GameState {
+    /// Partner of `declarer`, `None` if the called king cannot be found.
+    ///
+    /// Once the partner is revealed by playing the called king, they are taken from the calls.
+    /// Before that the king is looked for in the talon, the hands and the current stich. A king
+    /// hidden among unknown cards cannot be found.
+    pub fn partner(&self, declarer: usize) -> Option<Partner> {
+        let calls = &self.players[declarer].calls;
+        let Some(king) = calls.called_king else {
+            return Some(Partner::Alone);
+        };
+
+        let holder = if let Some(teammate) = calls.teammate {
+            Some(teammate)
+        } else if self.talon.iter().any(|half| half.cards.contains(king)) {
+            None
+        } else if let Some(player) =
+            (0..NUM_PLAYERS).find(|&player| self.players[player].hand.cards.contains(king))
+        {
+            Some(player)
+        } else {
+            let position = self.stich.iter().position(|card| card == king)?;
+            Some((self.leader() + position) % NUM_PLAYERS)
+        };
+        Some(match holder {
+            Some(player) if player != declarer => Partner::Player(player),
+            _ => Partner::Alone,
+        })
+    }
+
+    /// Whether the partner of `declarer` has revealed themselves by playing the called king.
+    pub fn partner_revealed(&self, declarer: usize) -> bool {
+        self.players[declarer].calls.teammate.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::card::Card;
+
+    // Rufer of player 1 calling the Kreuz König, player 3 holds it
+    const RUFER: &str = "XDX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#XK.........../#............/# R1XK-1 - /// - -";
+
+    #[test]
+    fn partner_in_hand() {
+        let game_state = GameState::from_str(RUFER).unwrap();
+        assert_eq!(game_state.partner(0), Some(Partner::Player(2)));
+        assert!(!game_state.partner_revealed(0));
+
+        // The king is hidden among unknown cards
+        let game_state = GameState::from_str(&RUFER.replace("#XK.", "#..")).unwrap();
+        assert_eq!(game_state.partner(0), None);
+    }
+
+    #[test]
+    fn partner_alone() {
+        // The king lies in the talon
+        let game_state =
+            GameState::from_str(&RUFER.replace("XDX10X9", "XKX10X9").replace("#XK", "#XD"))
+                .unwrap();
+        assert_eq!(game_state.partner(0), Some(Partner::Alone));
+
+        // The declarer called their own king
+        let mut game_state = GameState::from_str(RUFER).unwrap();
+        game_state.players[0].calls.called_king = Some(Card::HK);
+        assert_eq!(game_state.partner(0), Some(Partner::Alone));
+
+        // No king is called
+        let mut game_state = GameState::from_str(RUFER).unwrap();
+        game_state.players[0].calls.called_king = None;
+        assert_eq!(game_state.partner(0), Some(Partner::Alone));
+    }
+
+    #[test]
+    fn partner_revealed() {
+        // Player 3 made the first stich and leads with the king
+        let played =
+            "XDX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KB/#.........../#........../KP...#.........../#XK";
+        let game_state = GameState::from_str(&format!("{played} R1XK-1 3 /// - -")).unwrap();
+        assert_eq!(game_state.partner(0), Some(Partner::Player(2)));
+        assert!(!game_state.partner_revealed(0));
+
+        let game_state = GameState::from_str(&format!("{played} R1XK31 3 /// - -")).unwrap();
+        assert_eq!(game_state.partner(0), Some(Partner::Player(2)));
+        assert!(game_state.partner_revealed(0));
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::Display;
+
+use super::card_set::CardSet;
+use super::game::GameState;
+
+/// A Kartenstapel of a game state.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Pile {
+    /// Upper (0) or lower (1) half of the talon.
+    Talon(usize),
+    Hand(usize),
+    Stiche(usize),
+    /// The current stich.
+    Stich,
+}
+
+impl Pile {
+    /// All piles in TAF order.
+    pub const ALL: [Pile; 11] = [
+        Pile::Talon(0),
+        Pile::Talon(1),
+        Pile::Hand(0),
+        Pile::Stiche(0),
+        Pile::Hand(1),
+        Pile::Stiche(1),
+        Pile::Hand(2),
+        Pile::Stiche(2),
+        Pile::Hand(3),
+        Pile::Stiche(3),
+        Pile::Stich,
+    ];
+}
+
+impl Display for Pile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pile::Talon(0) => write!(f, "upper talon"),
+            Pile::Talon(_) => write!(f, "lower talon"),
+            Pile::Hand(player) => write!(f, "hand of player {}", player + 1),
+            Pile::Stiche(player) => write!(f, "stiche of player {}", player + 1),
+            Pile::Stich => write!(f, "current stich"),
+        }
+    }
+}
+
+impl GameState {
+    /// Cards of `pile`.
+    pub fn pile(&self, pile: Pile) -> CardSet {
+        match pile {
+            Pile::Talon(half) => self.talon[half].cards,
+            Pile::Hand(player) => self.players[player].hand.cards,
+            Pile::Stiche(player) => self.players[player].stiche.cards,
+            Pile::Stich => self.stich.card_set(),
+        }
+    }
+
+    /// Maximum number of cards `pile` can hold.
+    pub fn pile_capacity(&self, pile: Pile) -> usize {
+        match pile {
+            Pile::Talon(_) => self.talon[0].capacity(),
+            Pile::Hand(player) => self.players[player].hand.capacity(),
+            Pile::Stiche(player) => self.players[player].stiche.capacity(),
+            Pile::Stich => super::stich::STICH_SIZE,
+        }
+    }
+}
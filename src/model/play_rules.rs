@@ -0,0 +1,412 @@
+//! Which cards may be played into the current stich.
+
+use super::card::{Card, Suit};
+use super::card_set::CardSet;
+use super::game::{GameState, NUM_PLAYERS};
+use super::ruleset::Ruleset;
+use super::stich::Stich;
+
+/// Play obligations that apply on top of Farbzwang and Tarockzwang.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PlayRules {
+    /// A player who can win the stich has to do so (Stichzwang).
+    pub overtake: bool,
+    /// The Pagat may only be played as the last Tarock of a hand.
+    pub pagat_last: bool,
+}
+
+impl GameState {
+    /// Known cards `player` may play into the current stich under `rules`.
+    ///
+    /// See [`GameState::legal_plays_with`].
+    pub fn legal_plays(&self, player: usize, rules: &Ruleset) -> CardSet {
+        self.legal_plays_with(player, rules.play_rules(self.game_type()))
+    }
+
+    /// Known cards `player` may play into the current stich.
+    ///
+    /// The suit led has to be followed (Farbzwang). A player who cannot follow has to play a
+    /// Tarock (Tarockzwang) and only a player with neither may play any card. `rules` restrict
+    /// this further.
+    ///
+    /// Unknown cards of a hidden hand may be any card the hand can hold, so a card is legal if
+    /// the hand could hold it and it would be legal for at least one such hand. Empty if it is not
+    /// the turn of `player` or the Lizitation is not over.
+    pub fn legal_plays_with(&self, player: usize, rules: PlayRules) -> CardSet {
+        if self.bidding.is_some() || self.stich.is_full() || player != self.current_player() {
+            return CardSet::new();
+        }
+
+        let hand = self.players[player].hand.cards;
+        let known = hand.known();
+        let possible = self.possible_cards(player);
+        let tarock = CardSet::of_suit(Suit::Tarock);
+
+        let mut legal = possible;
+        // Known cards the player certainly may play, whatever their unknown cards are
+        let mut certain = known;
+        if let Some(led) = self.stich.first().and_then(Card::suit) {
+            let led_suit = CardSet::of_suit(led);
+            legal = possible.intersection(&led_suit);
+            if known.is_disjoint(&led_suit) {
+                legal = legal.union(&possible.intersection(&tarock));
+                if known.is_disjoint(&tarock) {
+                    legal = possible;
+                }
+            }
+            certain = if hand.unknown_len() == 0 {
+                legal
+            } else {
+                known.intersection(&led_suit)
+            };
+        }
+
+        if rules.overtake && !self.stich.is_empty() {
+            let winning: CardSet = legal
+                .iter()
+                .filter(|card| {
+                    let mut stich = self.stich;
+                    stich.push(*card);
+                    winning_position(&stich, self.kleinen_stechen_großen) == Some(self.stich.len())
+                })
+                .collect();
+            if !certain.is_disjoint(&winning) {
+                legal = winning;
+            }
+        }
+
+        if rules.pagat_last && legal.contains(Card::T1) {
+            let mut other_tarock = legal.intersection(&known).intersection(&tarock);
+            other_tarock.remove(Card::T1);
+            if !other_tarock.is_empty() {
+                legal.remove(Card::T1);
+            }
+        }
+        legal
+    }
+}
+
+/// Index of the player who wins `stich`, led by `leader`.
+///
+/// The highest Tarock wins, without one the highest card of the suit led. If
+/// [`GameState::kleinen_stechen_großen`] is played and Pagat, Mond and Sküs fall in the same stich,
+/// the Pagat wins. `None` if the stich is empty or holds unknown cards.
+pub fn trick_winner(stich: &Stich, leader: usize, state: &GameState) -> Option<usize> {
+    winning_position(stich, state.kleinen_stechen_großen)
+        .map(|position| (leader + position) % NUM_PLAYERS)
+}
+
+/// Position in play order of the card winning `stich`.
+fn winning_position(stich: &Stich, kleinen_stechen_großen: bool) -> Option<usize> {
+    if stich.contains(Card::UNKNOWN) {
+        return None;
+    }
+    if kleinen_stechen_großen
+        && [Card::T1, Card::T21, Card::T22]
+            .iter()
+            .all(|card| stich.contains(*card))
+    {
+        return stich.iter().position(|card| card == Card::T1);
+    }
+
+    let mut cards = stich.iter().enumerate();
+    let (mut best_position, mut best) = cards.next()?;
+    for (position, card) in cards {
+        if beats(card, best) {
+            (best_position, best) = (position, card);
+        }
+    }
+    Some(best_position)
+}
+
+/// Whether `card` beats `best`, the card currently winning the stich.
+///
+/// Tarock beats every other suit, otherwise only a higher card of the same suit wins.
+fn beats(card: Card, best: Card) -> bool {
+    match (card.suit(), best.suit()) {
+        (Some(Suit::Tarock), Some(suit)) if suit != Suit::Tarock => true,
+        (Some(suit), Some(best_suit)) if suit == best_suit => card.rank() > best.rank(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::game::CardCollection;
+    use std::str::FromStr;
+
+    fn legal_plays(taf: &str, player: usize) -> CardSet {
+        GameState::from_str(taf)
+            .unwrap()
+            .legal_plays(player, &Ruleset::default())
+    }
+
+    #[test]
+    fn legal_plays_lead() {
+        let taf = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - /// - -";
+        assert_eq!(
+            legal_plays(taf, 0),
+            CardCollection::<12>::from_str("HKHDT1T3T5T6K1K2K3K4KBKP")
+                .unwrap()
+                .cards
+        );
+        assert_eq!(legal_plays(taf, 1), CardSet::new());
+    }
+
+    #[test]
+    fn legal_plays_farbzwang() {
+        // Player 2 has to follow Karo
+        let taf = "XKX10X9/H4H3H2#HKHDT1T3T5T6K2K3K4KBKP/#K1HBT22XD/#............/#............../#KK R1XK-1 1 /// - -";
+        assert_eq!(legal_plays(taf, 1), CardSet::from_iter([Card::K1]));
+    }
+
+    #[test]
+    fn legal_plays_tarockzwang() {
+        // Player 2 is void in Karo and has to play a Tarock
+        let taf = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#HBT22T21XD/#............/#............../#KK R1XK-1 1 /// - -";
+        assert_eq!(
+            legal_plays(taf, 1),
+            CardSet::from_iter([Card::T22, Card::T21])
+        );
+
+        // Without Karo and Tarock any card may be played
+        let taf = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#HBXDX8X7/#............/#............../#KK R1XK-1 1 /// - -";
+        assert_eq!(
+            legal_plays(taf, 1),
+            CardSet::from_iter([Card::HB, Card::XD, Card::X8, Card::X7])
+        );
+
+        // A Tarock lead has to be followed with Tarock
+        let taf = "XKX10X9/H4H3H2#HKHDT1T3T5K1K2K3K4KBKP/#HBXDX8X7T22/#............/#............../#T6 R1XK-1 1 /// - -";
+        assert_eq!(legal_plays(taf, 1), CardSet::from_iter([Card::T22]));
+    }
+
+    #[test]
+    fn legal_plays_unknown() {
+        // Player 2 holds HB and unknown cards: HB is a Herz card, so Herz has to be followed
+        let herz = "XKX10X9/H4H3H2#HDT1T3T5T6K1K2K3K4KBKP/#HB........./#............/#............/HK#H1 R1XK-1 1 /// - -";
+        let legal = legal_plays(herz, 1);
+        assert!(legal.contains(Card::HB));
+        assert!(legal.contains(Card::HP));
+        assert!(!legal.contains(Card::T22));
+        assert!(!legal.contains(Card::H4));
+
+        // A hidden hand might be void in Herz and Tarock, so any card it can hold may be played
+        let hidden = "XKX10X9/H4H3H2#HDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/HK#H1 R1XK-1 1 /// - -";
+        let game_state = GameState::from_str(hidden).unwrap();
+        let legal = game_state.legal_plays(1, &Ruleset::default());
+        assert_eq!(legal, game_state.possible_cards(1));
+        assert!(legal.contains(Card::HP));
+        assert!(legal.contains(Card::T22));
+        assert!(legal.contains(Card::X8));
+
+        // Known Tarock but maybe no Herz: Herz or Tarock
+        let tarock = "XKX10X9/H4H3H2#HDT1T3T5T6K1K2K3K4KBKP/#T22.........../#............/#............/HK#H1 R1XK-1 1 /// - -";
+        let legal = legal_plays(tarock, 1);
+        assert!(legal.contains(Card::HP));
+        assert!(legal.contains(Card::T22));
+        assert!(legal.contains(Card::T21));
+        assert!(!legal.contains(Card::X8));
+    }
+
+    /// Player 2 follows a Herz Bube led by player 1 in game `game`.
+    fn follow_herz(game: &str, hand: &str) -> CardSet {
+        let taf = format!(
+            "XKX10X9/H3#HKH2T2T3T5T6K1K2K3K4KP/#{hand}/#............/#............/#HB {game} - /// - -"
+        );
+        GameState::from_str(&taf)
+            .unwrap()
+            .legal_plays(1, &Ruleset::default())
+    }
+
+    fn cards(cards: &str) -> CardSet {
+        CardCollection::<12>::from_str(cards).unwrap().cards
+    }
+
+    #[test]
+    fn legal_plays_positive_game() {
+        assert_eq!(follow_herz("R1XK-1", "HDH1X7T1"), cards("HDH1"));
+        assert_eq!(follow_herz("D1--1", "X7T1T4"), cards("T1T4"));
+    }
+
+    #[test]
+    fn legal_plays_overtake() {
+        for game in ["T1---", "B1---", "BO1---", "P11---", "PB21---", "PO31---"] {
+            // HD beats HB, H1 does not
+            assert_eq!(follow_herz(game, "HDH1X7T1"), cards("HD"), "{game}");
+            // Nothing beats HB, so anything that follows
+            assert_eq!(follow_herz(game, "H1H4X7T1"), cards("H1H4"), "{game}");
+            // Any Tarock beats Herz
+            assert_eq!(follow_herz(game, "X7T4T7"), cards("T4T7"), "{game}");
+        }
+
+        // Overtaking a Tarock with a Tarock
+        let taf = "XKX10X9/H4H3#HKHDT3T5T6K1K2K3K4KBKPH2/#T4T7T8X7/#............/#............/#T6 T1--- - /// - -";
+        assert_eq!(
+            GameState::from_str(taf)
+                .unwrap()
+                .legal_plays(1, &Ruleset::default()),
+            cards("T7T8")
+        );
+    }
+
+    #[test]
+    fn legal_plays_pagat_last() {
+        for game in ["T1---", "B1---", "BO1---", "P11---", "PB21---", "PO31---"] {
+            // The Pagat is kept while another Tarock is left
+            assert_eq!(follow_herz(game, "X7T1T4"), cards("T4"), "{game}");
+            // Unless it is the last one
+            assert_eq!(follow_herz(game, "X7T1"), cards("T1"), "{game}");
+
+            // Also when leading
+            let taf = format!(
+                "XKX10X9/H4H3H2#HKHDT2T3T5T6K1K2K3K4KBKP/#X7T1T4/#............/#............/# {game} 2 /// - -"
+            );
+            assert_eq!(
+                GameState::from_str(&taf)
+                    .unwrap()
+                    .legal_plays(1, &Ruleset::default()),
+                cards("X7T4"),
+                "{game}"
+            );
+        }
+    }
+
+    #[test]
+    fn legal_plays_negative_unknown() {
+        // Player 2 might not hold a Herz that beats HB, so H1 is allowed
+        let legal = follow_herz("B1---", "H1...........");
+        assert!(legal.contains(Card::H1));
+        assert!(legal.contains(Card::HD));
+        // But holding HD they certainly can overtake
+        assert_eq!(follow_herz("B1---", "HDH1.........."), cards("HDHP"));
+    }
+
+    #[test]
+    fn legal_plays_overtake_kleinen_stechen_großen() {
+        // With Mond and Sküs in the stich only the Pagat wins it
+        let taf = "XKX10X9/H4H3H2#HKHDT3T5T6K1K2K3K4KBKP/T2T4T7T8#T1T20X7/#............/#............/#T21T22 T1--- 4 /// J -";
+        assert_eq!(
+            GameState::from_str(taf)
+                .unwrap()
+                .legal_plays(1, &Ruleset::default()),
+            cards("T1")
+        );
+        let taf = taf.replace(" J ", " - ");
+        assert_eq!(
+            GameState::from_str(&taf)
+                .unwrap()
+                .legal_plays(1, &Ruleset::default()),
+            cards("T20")
+        );
+    }
+
+    fn stich(cards: &[Card]) -> Stich {
+        cards.iter().copied().collect()
+    }
+
+    #[test]
+    fn trick_winner_suits() {
+        let mut game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - /// - -",
+        )
+        .unwrap();
+        for kleinen_stechen_großen in [false, true] {
+            game_state.kleinen_stechen_großen = kleinen_stechen_großen;
+            let winner = |cards: &[Card], leader| trick_winner(&stich(cards), leader, &game_state);
+
+            // Highest card of the suit led
+            assert_eq!(
+                winner(&[Card::H4, Card::HK, Card::H1, Card::HD], 0),
+                Some(1)
+            );
+            assert_eq!(
+                winner(&[Card::X7, Card::X10, Card::X9, Card::X8], 2),
+                Some(3)
+            );
+            // Other suits do not count
+            assert_eq!(
+                winner(&[Card::H4, Card::XK, Card::PK, Card::KK], 1),
+                Some(1)
+            );
+            // Any Tarock beats the suit led
+            assert_eq!(
+                winner(&[Card::HK, Card::T1, Card::HD, Card::X7], 3),
+                Some(0)
+            );
+            assert_eq!(
+                winner(&[Card::HK, Card::T1, Card::T2, Card::X7], 3),
+                Some(1)
+            );
+            assert_eq!(
+                winner(&[Card::T5, Card::T4, Card::HK, Card::T3], 0),
+                Some(0)
+            );
+
+            assert_eq!(winner(&[Card::HK, Card::T1], 0), Some(1));
+            assert_eq!(winner(&[], 0), None);
+            assert_eq!(winner(&[Card::HK, Card::UNKNOWN, Card::T1], 0), None);
+        }
+    }
+
+    /// Every order of `cards`.
+    fn permutations(cards: &[Card]) -> Vec<Vec<Card>> {
+        if cards.is_empty() {
+            return vec![Vec::new()];
+        }
+        let mut orders = Vec::new();
+        for (index, card) in cards.iter().enumerate() {
+            let mut rest = cards.to_vec();
+            rest.remove(index);
+            for mut order in permutations(&rest) {
+                order.insert(0, *card);
+                orders.push(order);
+            }
+        }
+        orders
+    }
+
+    #[test]
+    fn trick_winner_kleinen_stechen_großen() {
+        let mut game_state = GameState::from_str(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - /// - -",
+        )
+        .unwrap();
+        let trull = [Card::T1, Card::T21, Card::T22];
+        let fillers = [Card::HK, Card::T5, Card::X7];
+
+        for kleinen_stechen_großen in [false, true] {
+            game_state.kleinen_stechen_großen = kleinen_stechen_großen;
+            for subset in 0..1 << trull.len() {
+                let mut cards: Vec<Card> = (0..trull.len())
+                    .filter(|index| subset & (1 << index) != 0)
+                    .map(|index| trull[index])
+                    .collect();
+                cards.extend(fillers.iter().take(4 - cards.len()));
+
+                let winning_card = if kleinen_stechen_großen && subset == 0b111 {
+                    Card::T1
+                } else {
+                    *cards
+                        .iter()
+                        .filter(|card| card.is_tarock())
+                        .max_by_key(|card| card.trump_number())
+                        .unwrap()
+                };
+
+                // Every order of the cards and every leader
+                for order in permutations(&cards) {
+                    let position = order.iter().position(|card| *card == winning_card).unwrap();
+                    for leader in 0..NUM_PLAYERS {
+                        assert_eq!(
+                            trick_winner(&stich(&order), leader, &game_state),
+                            Some((leader + position) % NUM_PLAYERS),
+                            "{order:?} led by {leader}, kleinen stechen die großen: {kleinen_stechen_großen}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
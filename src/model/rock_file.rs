@@ -0,0 +1,160 @@
+//! Reading and writing ROCK files, the history of a game as one TAF string per line.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::error::{MoveError, RockError};
+use super::game::GameState;
+use super::moves::Move;
+use super::ruleset::Ruleset;
+use super::stich::TakenStich;
+
+/// Starts a comment that runs until the end of the line.
+pub const COMMENT: char = ';';
+
+/// The positions of a game in the order they occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RockFile {
+    pub states: Vec<GameState>,
+}
+
+impl RockFile {
+    pub fn new(states: Vec<GameState>) -> Self {
+        Self { states }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, RockError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), RockError> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// The stiche taken between consecutive states under `rules`, in the order they were taken.
+    pub fn stich_history(&self, rules: &Ruleset) -> Result<Vec<TakenStich>, MoveError> {
+        let mut history = Vec::new();
+        for pair in self.states.windows(2) {
+            if let Move::TakeStich { player } = Move::between(&pair[0], &pair[1], rules)? {
+                history.push(TakenStich {
+                    stich: pair[0].stich,
+                    leader: pair[0].leader(),
+                    winner: player,
+                });
+            }
+        }
+        Ok(history)
+    }
+}
+
+/// Parses one TAF string per line. Blank lines and everything after a `;` are ignored.
+impl FromStr for RockFile {
+    type Err = RockError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut states = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            let taf = line.split(COMMENT).next().unwrap_or_default();
+            if taf.trim().is_empty() {
+                continue;
+            }
+            let game_state = GameState::from_str(taf).map_err(|error| RockError::Taf {
+                line: index + 1,
+                error,
+            })?;
+            states.push(game_state);
+        }
+        Ok(RockFile { states })
+    }
+}
+
+/// Writes every state as canonical TAF on its own line.
+impl Display for RockFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for game_state in &self.states {
+            writeln!(f, "{game_state}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::card::Card;
+    use crate::model::error::TafError;
+
+    const HISTORY: &str = "\
+; Rufer von Spieler:in 1 mit Kreuz König
+XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - 1K/T// - R12
+
+XKX10X9/H4H3H2#HDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/#HK R1XK-1 - 1K/T// - R12 ; Herz König ausgespielt
+";
+
+    const STICH_TAKEN: &str = "\
+/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HKX10X9#.........../#.........../#............/#HDH1HB R1XK-1 - /// - -
+/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HKX10X9#.........../#.........../#.........../#HDH1HBT22 R1XK-1 - /// - -
+/H4H3H2#T1T3T5T6K1K2K3K4KBKPXK/HKX10X9#.........../#.........../#.........../HDH1HBT22# R1XK-1 4 /// - -
+";
+
+    #[test]
+    fn rock_file_from_str() {
+        let rock_file = RockFile::from_str(HISTORY).unwrap();
+        assert_eq!(rock_file.states.len(), 2);
+        assert!(rock_file.states[0].stich.is_empty());
+        assert_eq!(rock_file.states[1].stich.first(), Some(Card::HK));
+
+        assert_eq!(RockFile::from_str("").unwrap(), RockFile::default());
+        assert_eq!(
+            RockFile::from_str("\n  \n; nur ein Kommentar\n").unwrap(),
+            RockFile::default()
+        );
+    }
+
+    #[test]
+    fn rock_file_from_str_errors() {
+        let error = RockFile::from_str(&format!("{HISTORY}\n\nX1/# R1XK-1 - /// - -")).unwrap_err();
+        let RockError::Taf { line, error } = error else {
+            panic!("expected a TAF error, got {error:?}");
+        };
+        assert_eq!(line, 7);
+        assert!(matches!(error, TafError::InvalidSyntax { .. }));
+    }
+
+    #[test]
+    fn rock_file_stich_history() {
+        let rock_file = RockFile::from_str(STICH_TAKEN).unwrap();
+        let history = rock_file.stich_history(&Ruleset::default()).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].leader, 0);
+        assert_eq!(history[0].winner, 3);
+        assert_eq!(history[0].winning_card(), Some(Card::T22));
+
+        let mut broken = rock_file.clone();
+        broken.states.swap(0, 2);
+        assert!(broken.stich_history(&Ruleset::default()).is_err());
+    }
+
+    #[test]
+    fn rock_file_round_trip() {
+        let rock_file = RockFile::from_str(HISTORY).unwrap();
+        assert_eq!(
+            RockFile::from_str(&rock_file.to_string()).unwrap(),
+            rock_file
+        );
+        assert_eq!(rock_file.to_string().lines().count(), 2);
+
+        let path = std::env::temp_dir().join(format!("pagengine-{}.rock", std::process::id()));
+        rock_file.write(&path).unwrap();
+        let read = RockFile::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), rock_file);
+
+        assert!(matches!(
+            RockFile::read(std::env::temp_dir().join("pagengine-missing.rock")),
+            Err(RockError::Io(_))
+        ));
+    }
+}
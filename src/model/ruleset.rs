@@ -0,0 +1,323 @@
+//! House rules and tariffs, which differ between regions and clubs.
+//!
+//! A ruleset can be written as a small TOML file. It may start from a named preset and then
+//! override single settings:
+//!
+//! ```toml
+//! preset = "wiener"
+//! pagat_last = false
+//!
+//! [games]
+//! T = 2      # value of the game
+//! S = false  # the game may not be played
+//!
+//! [bonuses]
+//! valat = 8
+//! ```
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::bonus::BonusValues;
+use super::error::RulesetError;
+use super::game_type::{GameType, NUM_GAME_TYPES};
+use super::play_rules::PlayRules;
+
+/// Starts a comment that runs until the end of the line.
+pub const COMMENT: char = '#';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ruleset {
+    /// Value of every game, indexed by [`GameType::index`]. `None` if the game may not be played.
+    pub values: [Option<u32>; NUM_GAME_TYPES],
+    pub bonuses: BonusValues,
+    /// In negative games a player who can win the stich has to do so.
+    pub overtake: bool,
+    /// In negative games the Pagat may only be played as the last Tarock of a hand.
+    pub pagat_last: bool,
+}
+
+impl Ruleset {
+    /// Names of the presets known to [`Ruleset::preset`].
+    pub const PRESETS: [&'static str; 2] = ["wiener", "grazer"];
+
+    /// Wiener Königrufen: every game is played and the Pagat has to be kept in negative games.
+    pub fn wiener() -> Self {
+        Self {
+            values: GameType::ALL.map(|typ| Some(typ.value())),
+            bonuses: BonusValues::default(),
+            overtake: true,
+            pagat_last: true,
+        }
+    }
+
+    /// Grazer Königrufen: without Sechserbock, Zwiccolo and Driccolo, Trischaken is worth 2 and the
+    /// Pagat may be played at any time.
+    pub fn grazer() -> Self {
+        let mut ruleset = Self::wiener();
+        for typ in [
+            GameType::S,
+            GameType::P2,
+            GameType::P3,
+            GameType::PB2,
+            GameType::PB3,
+            GameType::PO2,
+            GameType::PO3,
+        ] {
+            ruleset.values[typ.index()] = None;
+        }
+        ruleset.values[GameType::T.index()] = Some(2);
+        ruleset.pagat_last = false;
+        ruleset
+    }
+
+    /// The preset called `name`, see [`Ruleset::PRESETS`].
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "wiener" => Some(Self::wiener()),
+            "grazer" => Some(Self::grazer()),
+            _ => None,
+        }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, RulesetError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Whether `typ` may be played.
+    pub fn allows(&self, typ: GameType) -> bool {
+        self.values[typ.index()].is_some()
+    }
+
+    /// Value of `typ`, `None` if it may not be played.
+    pub fn value(&self, typ: GameType) -> Option<u32> {
+        self.values[typ.index()]
+    }
+
+    /// The play obligations of `typ`.
+    pub fn play_rules(&self, typ: Option<GameType>) -> PlayRules {
+        let negative = typ.is_some_and(|typ| typ.is_negative());
+        PlayRules {
+            overtake: negative && self.overtake,
+            pagat_last: negative && self.pagat_last,
+        }
+    }
+
+    fn set(&mut self, section: &str, key: &str, value: Value) -> Result<(), &'static str> {
+        match (section, key, value) {
+            ("", "preset", _) => return Err("the preset has to come first"),
+            ("", "overtake", Value::Bool(overtake)) => self.overtake = overtake,
+            ("", "pagat_last", Value::Bool(pagat_last)) => self.pagat_last = pagat_last,
+            ("games", typ, value) => {
+                let typ = GameType::from_str(typ).map_err(|_| "unknown game")?;
+                self.values[typ.index()] = match value {
+                    Value::Bool(false) => None,
+                    Value::Bool(true) => Some(self.value(typ).unwrap_or(typ.value())),
+                    Value::Number(value) => Some(value),
+                    Value::String(_) => return Err("expected a number or a boolean"),
+                };
+            }
+            ("bonuses", ansage, Value::Number(value)) => {
+                let bonuses = &mut self.bonuses;
+                *match ansage {
+                    "pagat" => &mut bonuses.pagat,
+                    "uhu" => &mut bonuses.uhu,
+                    "pelikan" => &mut bonuses.pelikan,
+                    "quapil" => &mut bonuses.quapil,
+                    "trull" => &mut bonuses.trull,
+                    "kings" => &mut bonuses.kings,
+                    "ultimo" => &mut bonuses.ultimo,
+                    "valat" => &mut bonuses.valat,
+                    _ => return Err("unknown bonus"),
+                } = value;
+            }
+            ("", "overtake" | "pagat_last", _) => return Err("expected a boolean"),
+            ("bonuses", _, _) => return Err("expected a number"),
+            ("", _, _) => return Err("unknown setting"),
+            _ => return Err("unknown section"),
+        }
+        Ok(())
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::wiener()
+    }
+}
+
+/// Parses the TOML subset described in the [module documentation](self). Settings that are not
+/// given are taken from the preset, or from the Wiener rules without one.
+impl FromStr for Ruleset {
+    type Err = RulesetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ruleset = Ruleset::default();
+        let mut section = "";
+        let mut first = true;
+        for (index, line) in s.lines().enumerate() {
+            let invalid = |reason| RulesetError::Invalid {
+                line: index + 1,
+                reason,
+            };
+            let line = line.split(COMMENT).next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                section = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| invalid("expected `]`"))?
+                    .trim();
+            } else {
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| invalid("expected `key = value`"))?;
+                let key = key.trim();
+                let value = Value::from_str(value.trim()).map_err(invalid)?;
+                match (first && section.is_empty() && key == "preset", value) {
+                    (true, Value::String(name)) => {
+                        ruleset = Ruleset::preset(name).ok_or_else(|| invalid("unknown preset"))?;
+                    }
+                    (true, _) => return Err(invalid("expected a string")),
+                    (false, value) => ruleset.set(section, key, value).map_err(invalid)?,
+                }
+            }
+            first = false;
+        }
+        Ok(ruleset)
+    }
+}
+
+/// Value of a setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value<'a> {
+    Bool(bool),
+    Number(u32),
+    String(&'a str),
+}
+
+impl<'a> Value<'a> {
+    fn from_str(s: &'a str) -> Result<Self, &'static str> {
+        match s {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => {
+                if let Some(string) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    Ok(Value::String(string))
+                } else {
+                    s.parse()
+                        .map(Value::Number)
+                        .map_err(|_| "expected a boolean, a number or a string")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruleset_presets() {
+        let wiener = Ruleset::wiener();
+        assert_eq!(Ruleset::default(), wiener);
+        assert!(wiener.allows(GameType::S));
+        assert_eq!(wiener.value(GameType::D), Some(4));
+        assert_eq!(
+            wiener.play_rules(Some(GameType::B)),
+            PlayRules {
+                overtake: true,
+                pagat_last: true
+            }
+        );
+        assert_eq!(wiener.play_rules(Some(GameType::R)), PlayRules::default());
+
+        let grazer = Ruleset::grazer();
+        assert!(!grazer.allows(GameType::S));
+        assert_eq!(grazer.value(GameType::T), Some(2));
+        assert!(!grazer.play_rules(Some(GameType::B)).pagat_last);
+
+        for name in Ruleset::PRESETS {
+            assert!(Ruleset::preset(name).is_some());
+        }
+        assert_eq!(Ruleset::preset("Grazer"), Some(grazer));
+        assert_eq!(Ruleset::preset("tiroler"), None);
+    }
+
+    #[test]
+    fn ruleset_from_str() {
+        assert_eq!(Ruleset::from_str("").unwrap(), Ruleset::wiener());
+
+        let ruleset = Ruleset::from_str(
+            "\
+# Unser Clubtarock
+preset = \"grazer\"
+pagat_last = true
+
+[games]
+S = true     # Sechserbock doch erlaubt
+T = 3
+SPD = false
+
+[bonuses]
+valat = 8
+",
+        )
+        .unwrap();
+        assert!(ruleset.pagat_last);
+        assert_eq!(ruleset.value(GameType::S), Some(2));
+        assert_eq!(ruleset.value(GameType::T), Some(3));
+        assert!(!ruleset.allows(GameType::SPD));
+        assert!(!ruleset.allows(GameType::P2));
+        assert_eq!(ruleset.bonuses.valat, 8);
+        assert_eq!(ruleset.bonuses.pagat, 1);
+    }
+
+    #[test]
+    fn ruleset_from_str_errors() {
+        let error = |s| match Ruleset::from_str(s) {
+            Err(RulesetError::Invalid { line, reason }) => (line, reason),
+            result => panic!("expected an invalid line, got {result:?}"),
+        };
+        assert_eq!(
+            error("overtake = true\npreset = \"wiener\""),
+            (2, "the preset has to come first")
+        );
+        assert_eq!(error("preset = \"tiroler\""), (1, "unknown preset"));
+        assert_eq!(error("preset = 1"), (1, "expected a string"));
+        assert_eq!(error("\n\novertake"), (3, "expected `key = value`"));
+        assert_eq!(error("overtake = 1"), (1, "expected a boolean"));
+        assert_eq!(
+            error("overtake = ja"),
+            (1, "expected a boolean, a number or a string")
+        );
+        assert_eq!(error("farbzwang = true"), (1, "unknown setting"));
+        assert_eq!(error("[games\nT = 1"), (1, "expected `]`"));
+        assert_eq!(error("[games]\nX = 1"), (2, "unknown game"));
+        assert_eq!(
+            error("[games]\nT = \"1\""),
+            (2, "expected a number or a boolean")
+        );
+        assert_eq!(error("[bonuses]\nmond = 1"), (2, "unknown bonus"));
+        assert_eq!(error("[bonuses]\npagat = true"), (2, "expected a number"));
+        assert_eq!(error("[prices]\nT = 1"), (2, "unknown section"));
+    }
+
+    #[test]
+    fn ruleset_read() {
+        let path = std::env::temp_dir().join(format!("pagengine-{}.toml", std::process::id()));
+        fs::write(&path, "preset = \"grazer\"\n").unwrap();
+        let read = Ruleset::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), Ruleset::grazer());
+
+        assert!(matches!(
+            Ruleset::read(std::env::temp_dir().join("pagengine-missing.toml")),
+            Err(RulesetError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! Outcome of a game and the payments between the players.
+
+use super::counting::Points;
+use super::game::{GameState, NUM_PLAYERS, TALON_HALF_SIZE};
+use super::game_type::GameType;
+use super::partner::Partner;
+use super::ruleset::Ruleset;
+use super::spritzen::SpritzenTarget;
+use super::stich::STICH_SIZE;
+
+impl GameState {
+    /// Whether every card has been played and the last stich has been taken.
+    pub fn is_finished(&self) -> bool {
+        self.stich.is_empty()
+            && self
+                .players
+                .iter()
+                .all(|player| player.hand.cards.is_empty())
+    }
+
+    /// Indices of all players who declared a game.
+    pub fn declarers(&self) -> Vec<usize> {
+        (0..NUM_PLAYERS)
+            .filter(|&player| self.players[player].calls.typ.is_some())
+            .collect()
+    }
+
+    /// Players playing on the side of `declarer`, including the declarer. `None` if the partner
+    /// cannot be found, see [`GameState::partner`].
+    pub fn declarer_team(&self, declarer: usize) -> Option<Vec<usize>> {
+        Some(match self.partner(declarer)? {
+            Partner::Player(partner) => vec![declarer, partner],
+            Partner::Alone => vec![declarer],
+        })
+    }
+
+    /// Number of stiche `player` has made.
+    ///
+    /// Cards the declarer laid down from the talon are not counted as a stich.
+    pub fn stiche_count(&self, player: usize) -> usize {
+        let laid_down = match self.players[player].calls.typ {
+            Some(typ) if typ.takes_whole_talon() => 2 * TALON_HALF_SIZE,
+            Some(typ) if typ.takes_talon_half() => TALON_HALF_SIZE,
+            _ => 0,
+        };
+        self.players[player]
+            .stiche
+            .cards
+            .len()
+            .saturating_sub(laid_down)
+            / STICH_SIZE
+    }
+
+    /// Whether `declarer` won their game, `None` if it is not decided yet or depends on unknown
+    /// cards.
+    ///
+    /// - In positive games the declarer's team needs at least 35⅔ points.
+    /// - In Bettler and Piccolo games the declarer has to make exactly the required number of
+    ///   stiche. Making one too many loses the game at once.
+    /// - In Trischaken the player with the most points loses.
+    pub fn declarer_wins(&self, declarer: usize) -> Option<bool> {
+        let typ = self.players[declarer].calls.typ?;
+        if let Some(required) = typ.required_stiche() {
+            let made = self.stiche_count(declarer);
+            if made > required {
+                return Some(false);
+            }
+            return self.is_finished().then_some(made == required);
+        }
+        if !self.is_finished() {
+            return None;
+        }
+        if typ == GameType::T {
+            return Some(!self.trischaken_losers()?.contains(&declarer));
+        }
+        Some(self.team_points(&self.declarer_team(declarer)?)?.wins())
+    }
+
+    /// Amount every player wins or pays (if negative) in a finished game, `None` if the game is not
+    /// finished or the outcome depends on unknown cards.
+    ///
+    /// Every opponent pays the value of the game in `rules`, multiplied by its Spritze, to the
+    /// declarer's team or receives it from them. Games `rules` do not allow cannot be settled.
+    /// When several players play a Piccolo bei, each of them is settled on their own. In
+    /// Trischaken every loser pays every other player.
+    pub fn settlement(&self, rules: &Ruleset) -> Option<[i32; NUM_PLAYERS]> {
+        if !self.is_finished() {
+            return None;
+        }
+
+        let mut balance = [0; NUM_PLAYERS];
+        for declarer in self.declarers() {
+            let calls = &self.players[declarer].calls;
+            let typ = calls.typ?;
+            let value =
+                (rules.value(typ)? * calls.spritzen.multiplier(SpritzenTarget::Game)) as i32;
+
+            if typ == GameType::T {
+                let losers = self.trischaken_losers()?;
+                for loser in losers.iter().copied() {
+                    for winner in (0..NUM_PLAYERS).filter(|player| !losers.contains(player)) {
+                        balance[loser] -= value;
+                        balance[winner] += value;
+                    }
+                }
+                continue;
+            }
+
+            let sign = if self.declarer_wins(declarer)? { 1 } else { -1 };
+            self.pay_team(&mut balance, declarer, sign * value)?;
+        }
+        Some(balance)
+    }
+
+    /// Moves `amount` from every opponent to the team of `declarer`, split evenly between its
+    /// members. The team pays if `amount` is negative. `None` if the team is not known.
+    pub(crate) fn pay_team(
+        &self,
+        balance: &mut [i32; NUM_PLAYERS],
+        declarer: usize,
+        amount: i32,
+    ) -> Option<()> {
+        let team = self.declarer_team(declarer)?;
+        let opponents: Vec<_> = (0..NUM_PLAYERS)
+            .filter(|player| !team.contains(player))
+            .collect();
+        for &member in &team {
+            balance[member] += amount * opponents.len() as i32 / team.len() as i32;
+        }
+        for &opponent in &opponents {
+            balance[opponent] -= amount;
+        }
+        Some(())
+    }
+
+    /// Players with the most points in Trischaken.
+    fn trischaken_losers(&self) -> Option<Vec<usize>> {
+        let points = (0..NUM_PLAYERS)
+            .map(|player| self.stiche_points(player))
+            .collect::<Option<Vec<Points>>>()?;
+        let most = points.iter().max().copied()?;
+        Some(
+            (0..NUM_PLAYERS)
+                .filter(|&player| points[player] == most)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::card::Card;
+
+    // Player 1 made all colour cards in a Dreier, the other players split the Tarock
+    const DREIER: &str = "/#/HKHDHPHBH1H2H3H4PKPDPPPBP10P9P8P7KKKDKPKBK1K2K3K4XKXDXPXBX10X9X8X7#/T1T2T3T4T5T6T7T8#/T9T10T11T12T13T14T15T16#/T17T18T19T20T21T22# D1--1 2 /// - -";
+
+    #[test]
+    fn positive_game() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+        assert!(game_state.is_finished());
+        assert_eq!(game_state.declarers(), vec![0]);
+        assert_eq!(game_state.stiche_count(0), 7);
+        assert_eq!(game_state.declarer_wins(0), Some(true));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([12, -4, -4, -4])
+        );
+
+        let stiche = game_state.players[0].stiche;
+        game_state.players[0].stiche = game_state.players[1].stiche;
+        game_state.players[1].stiche = stiche;
+        assert_eq!(game_state.declarer_wins(0), Some(false));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([-12, 4, 4, 4])
+        );
+    }
+
+    #[test]
+    fn positive_game_gespritzt() {
+        let game_state = GameState::from_str(&DREIER.replace("- -", "- D12+")).unwrap();
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([48, -16, -16, -16])
+        );
+    }
+
+    #[test]
+    fn unfinished_game() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+        game_state.players[2].hand.cards.insert(Card::T9);
+        assert!(!game_state.is_finished());
+        assert_eq!(game_state.declarer_wins(0), None);
+        assert_eq!(game_state.settlement(&Ruleset::default()), None);
+    }
+
+    #[test]
+    fn rufer_with_partner() {
+        // Player 2 played the called king and made the small Tarock
+        let game_state = GameState::from_str(&DREIER.replace("D1--1", "R1XK21")).unwrap();
+        assert_eq!(game_state.declarer_team(0), Some(vec![0, 1]));
+        assert_eq!(game_state.declarer_wins(0), Some(true));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([1, 1, -1, -1])
+        );
+
+        // Without knowing who played the king, the teams are unknown
+        let game_state = GameState::from_str(&DREIER.replace("D1--1", "R1XK-1")).unwrap();
+        assert_eq!(game_state.declarer_team(0), None);
+        assert_eq!(game_state.settlement(&Ruleset::default()), None);
+    }
+
+    #[test]
+    fn negative_games() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+
+        // Player 3 makes two stiche
+        game_state.players[0].calls.typ = None;
+        game_state.players[2].calls.typ = Some(GameType::P2);
+        assert_eq!(game_state.declarer_wins(2), Some(true));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([-2, -2, 6, -2])
+        );
+
+        game_state.players[2].calls.typ = Some(GameType::P1);
+        assert_eq!(game_state.declarer_wins(2), Some(false));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([2, 2, -6, 2])
+        );
+
+        // Too many stiche lose before the game is over
+        game_state.players[1].hand.cards.insert(Card::T9);
+        assert_eq!(game_state.declarer_wins(2), Some(false));
+        assert_eq!(game_state.settlement(&Ruleset::default()), None);
+    }
+
+    #[test]
+    fn piccolo_bei() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+        game_state.players[0].calls.typ = None;
+        game_state.players[1].calls.typ = Some(GameType::PB1);
+        game_state.players[2].calls.typ = Some(GameType::PB2);
+        assert_eq!(game_state.declarers(), vec![1, 2]);
+        assert_eq!(game_state.declarer_wins(1), Some(false));
+        assert_eq!(game_state.declarer_wins(2), Some(true));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([0, -8, 8, 0])
+        );
+    }
+
+    #[test]
+    fn settlement_with_ruleset() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+        game_state.players[0].calls.typ = None;
+        game_state.players[3].calls.typ = Some(GameType::T);
+        assert_eq!(
+            game_state.settlement(&Ruleset::grazer()),
+            Some([-6, 2, 2, 2])
+        );
+
+        game_state.players[3].calls.typ = Some(GameType::P2);
+        assert_eq!(game_state.settlement(&Ruleset::grazer()), None);
+    }
+
+    #[test]
+    fn trischaken() {
+        let mut game_state = GameState::from_str(DREIER).unwrap();
+        game_state.players[0].calls.typ = None;
+        game_state.players[3].calls.typ = Some(GameType::T);
+        assert_eq!(game_state.declarer_wins(3), Some(true));
+        assert_eq!(
+            game_state.settlement(&Ruleset::default()),
+            Some([-3, 1, 1, 1])
+        );
+    }
+}
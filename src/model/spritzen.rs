@@ -0,0 +1,132 @@
+use super::ansage::{Ansage, NUM_ANSAGEN};
+
+/// Doubling level of a Spritze.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SpritzenLevel {
+    Kontra,
+    Rekontra,
+    Subkontra,
+}
+
+impl SpritzenLevel {
+    pub fn multiplier(self) -> u32 {
+        match self {
+            SpritzenLevel::Kontra => 2,
+            SpritzenLevel::Rekontra => 4,
+            SpritzenLevel::Subkontra => 8,
+        }
+    }
+
+    /// Level reached after another player doubles this one, `None` above Subkontra.
+    pub fn next(self) -> Option<SpritzenLevel> {
+        match self {
+            SpritzenLevel::Kontra => Some(SpritzenLevel::Rekontra),
+            SpritzenLevel::Rekontra => Some(SpritzenLevel::Subkontra),
+            SpritzenLevel::Subkontra => None,
+        }
+    }
+}
+
+/// What a Spritze doubles: the game itself or a single Ansage.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SpritzenTarget {
+    Game,
+    Ansage(Ansage),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Spritze {
+    /// Index of the player who gave the Kontra.
+    pub by: usize,
+    pub level: SpritzenLevel,
+}
+
+/// All Spritzen against the game and the Ansagen of a single player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Spritzen {
+    pub game: Option<Spritze>,
+    pub ansagen: [Option<Spritze>; NUM_ANSAGEN],
+}
+
+impl Spritzen {
+    pub fn get(&self, target: SpritzenTarget) -> Option<Spritze> {
+        match target {
+            SpritzenTarget::Game => self.game,
+            SpritzenTarget::Ansage(ansage) => self.ansagen[ansage.index()],
+        }
+    }
+
+    pub fn set(&mut self, target: SpritzenTarget, spritze: Option<Spritze>) {
+        match target {
+            SpritzenTarget::Game => self.game = spritze,
+            SpritzenTarget::Ansage(ansage) => self.ansagen[ansage.index()] = spritze,
+        }
+    }
+
+    /// Factor the value of `target` is multiplied with, 1 if it was not gespritzt.
+    pub fn multiplier(&self, target: SpritzenTarget) -> u32 {
+        self.get(target)
+            .map(|spritze| spritze.level.multiplier())
+            .unwrap_or(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.game.is_none() && self.ansagen.iter().all(Option::is_none)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spritzen_multiplier() {
+        let mut spritzen = Spritzen::default();
+        assert!(spritzen.is_empty());
+        assert_eq!(spritzen.multiplier(SpritzenTarget::Game), 1);
+
+        spritzen.set(
+            SpritzenTarget::Game,
+            Some(Spritze {
+                by: 1,
+                level: SpritzenLevel::Kontra,
+            }),
+        );
+        spritzen.set(
+            SpritzenTarget::Ansage(Ansage::Pagat),
+            Some(Spritze {
+                by: 2,
+                level: SpritzenLevel::Subkontra,
+            }),
+        );
+
+        assert!(!spritzen.is_empty());
+        assert_eq!(spritzen.multiplier(SpritzenTarget::Game), 2);
+        assert_eq!(
+            spritzen.multiplier(SpritzenTarget::Ansage(Ansage::Pagat)),
+            8
+        );
+        assert_eq!(
+            spritzen.multiplier(SpritzenTarget::Ansage(Ansage::Trull)),
+            1
+        );
+        assert_eq!(
+            spritzen
+                .get(SpritzenTarget::Ansage(Ansage::Pagat))
+                .unwrap()
+                .by,
+            2
+        );
+    }
+
+    #[test]
+    fn spritzen_level_next() {
+        assert_eq!(SpritzenLevel::Kontra.next(), Some(SpritzenLevel::Rekontra));
+        assert_eq!(
+            SpritzenLevel::Rekontra.next(),
+            Some(SpritzenLevel::Subkontra)
+        );
+        assert_eq!(SpritzenLevel::Subkontra.next(), None);
+        assert_eq!(SpritzenLevel::Rekontra.multiplier(), 4);
+    }
+}
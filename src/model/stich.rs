@@ -0,0 +1,169 @@
+use std::fmt::Display;
+
+use super::card::Card;
+use super::card_set::CardSet;
+
+pub const STICH_SIZE: usize = 4;
+
+/// The current stich, keeping the cards in the order they were played.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Stich {
+    cards: [Option<Card>; STICH_SIZE],
+    len: usize,
+}
+
+impl Stich {
+    pub const fn new() -> Self {
+        Self {
+            cards: [None; STICH_SIZE],
+            len: 0,
+        }
+    }
+
+    /// Plays `card` into the stich.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stich is already full.
+    pub fn push(&mut self, card: Card) {
+        assert!(!self.is_full(), "stich is already full");
+        self.cards[self.len] = Some(card);
+        self.len += 1;
+    }
+
+    /// Takes back the card played last.
+    pub fn pop(&mut self) -> Option<Card> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.cards[self.len].take()
+    }
+
+    /// The card that was led.
+    pub fn first(&self) -> Option<Card> {
+        self.cards[0]
+    }
+
+    /// The `index`-th card played into the stich.
+    pub fn get(&self, index: usize) -> Option<Card> {
+        self.cards.get(index).copied().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == STICH_SIZE
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.iter().any(|played| played == card)
+    }
+
+    /// Iterates over the cards in the order they were played.
+    pub fn iter(&self) -> impl Iterator<Item = Card> + '_ {
+        self.cards.iter().flatten().copied()
+    }
+
+    pub fn card_set(&self) -> CardSet {
+        self.iter().collect()
+    }
+}
+
+impl FromIterator<Card> for Stich {
+    /// # Panics
+    ///
+    /// Panics if the iterator yields more than four cards.
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut stich = Stich::new();
+        for card in iter {
+            stich.push(card);
+        }
+        stich
+    }
+}
+
+impl Display for Stich {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for card in self.iter() {
+            write!(f, "{card}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A stich that has been taken, with the players who led and won it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TakenStich {
+    pub stich: Stich,
+    pub leader: usize,
+    pub winner: usize,
+}
+
+impl TakenStich {
+    /// The card the winner played.
+    pub fn winning_card(&self) -> Option<Card> {
+        self.stich
+            .get((self.winner + STICH_SIZE - self.leader) % STICH_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stich_push_pop() {
+        let mut stich = Stich::new();
+        assert!(stich.is_empty());
+        assert_eq!(stich.first(), None);
+
+        stich.push(Card::HK);
+        stich.push(Card::UNKNOWN);
+        stich.push(Card::T22);
+        assert_eq!(stich.len(), 3);
+        assert_eq!(stich.first(), Some(Card::HK));
+        assert_eq!(stich.get(1), Some(Card::UNKNOWN));
+        assert_eq!(stich.get(3), None);
+        assert!(stich.contains(Card::T22));
+        assert!(!stich.is_full());
+
+        stich.push(Card::T21);
+        assert!(stich.is_full());
+        assert_eq!(stich.to_string(), "HK.T22T21");
+
+        assert_eq!(stich.pop(), Some(Card::T21));
+        assert_eq!(stich.pop(), Some(Card::T22));
+        assert_eq!(stich, Stich::from_iter([Card::HK, Card::UNKNOWN]));
+    }
+
+    #[test]
+    fn stich_order() {
+        assert_ne!(
+            Stich::from_iter([Card::HK, Card::T22]),
+            Stich::from_iter([Card::T22, Card::HK])
+        );
+    }
+
+    #[test]
+    fn taken_stich_winning_card() {
+        let taken = TakenStich {
+            stich: Stich::from_iter([Card::HK, Card::H1, Card::T1, Card::HD]),
+            leader: 3,
+            winner: 1,
+        };
+        assert_eq!(taken.winning_card(), Some(Card::T1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn stich_overfull() {
+        Stich::from_iter([Card::H1, Card::H2, Card::H3, Card::H4, Card::HK]);
+    }
+}
@@ -0,0 +1,148 @@
+//! Proptest strategies for the model types.
+
+use std::str::FromStr;
+
+use proptest::prelude::*;
+
+use super::ansage::Ansage;
+use super::card::Card;
+use super::card_set::CardSet;
+use super::game::{Calls, CardCollection, GameState, Player};
+use super::game_type::GameType;
+use super::spritzen::{Spritze, SpritzenLevel, SpritzenTarget};
+use super::stich::{Stich, STICH_SIZE};
+use super::talon::TalonChoice;
+
+pub fn arb_card() -> impl Strategy<Value = Card> {
+    r"[HK][1-4KDPB]|[PX]([7-9KDPB]|10)|T(2[0-2]|1[0-9]|[1-9])|\."
+        .prop_map(|s| Card::from_str(&s).unwrap())
+}
+
+pub fn arb_game_type() -> impl Strategy<Value = GameType> {
+    r"SPD|SR|BR|BO|PD|SD|PO[1-3]|PB[1-3]|P[1-3]|[TRSBD]"
+        .prop_map(|s| GameType::from_str(&s).unwrap())
+}
+
+pub fn arb_card_collection<const N: usize>() -> impl Strategy<Value = CardCollection<N>> {
+    prop::collection::vec(arb_card(), 0..=N).prop_map(|cards| CardCollection {
+        cards: cards.into_iter().collect(),
+        excluded: CardSet::new(),
+    })
+}
+
+pub fn arb_stich() -> impl Strategy<Value = Stich> {
+    prop::collection::vec(arb_card(), 0..=STICH_SIZE)
+        .prop_filter("no known card twice", |cards| {
+            cards.iter().copied().collect::<CardSet>().len() == cards.len()
+        })
+        .prop_map(|cards| cards.into_iter().collect())
+}
+
+fn arb_spritze(player: usize) -> impl Strategy<Value = Option<Spritze>> {
+    let level = prop_oneof![
+        Just(SpritzenLevel::Kontra),
+        Just(SpritzenLevel::Rekontra),
+        Just(SpritzenLevel::Subkontra),
+    ];
+    prop::option::of((1..4usize, level).prop_map(move |(offset, level)| Spritze {
+        by: (player + offset) % 4,
+        level,
+    }))
+}
+
+fn arb_calls(player: usize) -> impl Strategy<Value = Calls> {
+    (
+        prop::collection::vec(any::<bool>(), Ansage::ALL.len()),
+        prop::collection::vec(arb_spritze(player), Ansage::ALL.len()),
+    )
+        .prop_map(|(announced, spritzen)| {
+            let mut calls = Calls::default();
+            for ((ansage, announced), spritze) in Ansage::ALL.iter().zip(announced).zip(spritzen) {
+                if announced {
+                    calls.announce(*ansage);
+                    calls.spritzen.set(SpritzenTarget::Ansage(*ansage), spritze);
+                }
+            }
+            calls
+        })
+}
+
+fn arb_player(player: usize) -> impl Strategy<Value = Player> {
+    (
+        arb_card_collection(),
+        arb_card_collection(),
+        arb_calls(player),
+    )
+        .prop_map(|(hand, stiche, calls)| Player {
+            hand,
+            stiche,
+            calls,
+        })
+}
+
+pub fn arb_game_state() -> impl Strategy<Value = GameState> {
+    let declarers = prop::collection::vec(prop::option::of(arb_game_type()), 4)
+        .prop_filter("at least one declarer", |typs| {
+            typs.iter().any(Option::is_some)
+        });
+    let king = prop::option::of(prop::sample::select(vec![
+        Card::HK,
+        Card::KK,
+        Card::PK,
+        Card::XK,
+    ]));
+    let teammate = prop::option::of(0..4usize);
+    let talon = prop::option::of(prop::sample::select(vec![
+        TalonChoice::Upper,
+        TalonChoice::Lower,
+        TalonChoice::Both,
+    ]));
+    let game_spritzen = (
+        arb_spritze(0),
+        arb_spritze(1),
+        arb_spritze(2),
+        arb_spritze(3),
+    );
+
+    (
+        [arb_player(0), arb_player(1), arb_player(2), arb_player(3)],
+        arb_stich(),
+        [arb_card_collection(), arb_card_collection()],
+        prop::option::of(0..4usize),
+        any::<bool>(),
+        (declarers, king, teammate, talon, game_spritzen),
+    )
+        .prop_map(
+            |(mut players, stich, talon, last_stich, kleinen_stechen_großen, game)| {
+                let (typs, king, teammate, taken_talon, game_spritzen) = game;
+                let game_spritzen = [
+                    game_spritzen.0,
+                    game_spritzen.1,
+                    game_spritzen.2,
+                    game_spritzen.3,
+                ];
+                for ((player, typ), spritze) in players.iter_mut().zip(typs).zip(game_spritzen) {
+                    if let Some(typ) = typ {
+                        player.calls.typ = Some(typ);
+                        player.calls.called_king = king;
+                        player.calls.teammate = teammate;
+                        player.calls.taken_talon = taken_talon.unwrap_or(TalonChoice::untaken(typ));
+                        // Trischaken cannot be gespritzt
+                        if typ != GameType::T {
+                            player.calls.spritzen.set(SpritzenTarget::Game, spritze);
+                        }
+                    }
+                }
+                let mut game_state = GameState {
+                    players,
+                    stich,
+                    talon,
+                    last_stich,
+                    kleinen_stechen_großen,
+                    bidding: None,
+                };
+                game_state.update_exclusions();
+                game_state
+            },
+        )
+}
@@ -0,0 +1,250 @@
+//! Talon exchange before the first stich.
+//!
+//! In games that take the talon, the declarer turns it up and takes one half of it, or both in a
+//! Sechserbock. Then they lay down (verlegen) as many cards as they took onto their stiche.
+//! Kings and Trullstücke may never be laid down. Tarock may only be laid down if the declarer
+//! does not hold enough other cards, and laid down Tarock are shown to everyone.
+//!
+//! What happens to the rest of the talon depends on the game, see
+//! [`GameType::talon_owner`](super::game_type::GameType::talon_owner). In a Rufer the opponents
+//! take the half the declarer did not choose. In a Dreier it stays with the declarer and is
+//! counted for them. In Solo games nobody looks at the talon, and the opponents take it as a
+//! whole.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::card::Rank;
+use super::card_set::CardSet;
+use super::game::{GameState, HAND_SIZE};
+use super::game_type::{GameType, TalonOwner};
+
+/// What happens to the talon in a game, the *Talon* of the TAF game group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TalonChoice {
+    /// The declarer takes the upper half, `1` in TAF.
+    Upper,
+    /// The declarer takes the lower half, `2` in TAF.
+    Lower,
+    /// The declarer takes both halves in a Sechserbock, `12` in TAF.
+    Both,
+    /// No talon is taken, or the declarer has not chosen a half yet.
+    #[default]
+    None,
+    /// The whole talon goes to the opponents untouched, as in Solo games. Written as `-` in TAF
+    /// like [`TalonChoice::None`].
+    ToOpponents,
+}
+
+impl TalonChoice {
+    /// The choice written as `-` in TAF for a game of `typ`.
+    pub fn untaken(typ: GameType) -> Self {
+        if typ.talon_owner() == TalonOwner::Opponents
+            && !typ.takes_talon_half()
+            && !typ.takes_whole_talon()
+        {
+            TalonChoice::ToOpponents
+        } else {
+            TalonChoice::None
+        }
+    }
+
+    /// Index of the talon half the declarer chose, `None` unless they chose exactly one.
+    pub fn half(self) -> Option<usize> {
+        match self {
+            TalonChoice::Upper => Some(0),
+            TalonChoice::Lower => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Whether this choice fits a game of `typ`. Not having chosen yet fits every game.
+    pub fn fits(self, typ: GameType) -> bool {
+        match self {
+            TalonChoice::Upper | TalonChoice::Lower => typ.takes_talon_half(),
+            TalonChoice::Both => typ.takes_whole_talon(),
+            TalonChoice::None => true,
+            TalonChoice::ToOpponents => Self::untaken(typ) == TalonChoice::ToOpponents,
+        }
+    }
+}
+
+/// Parses `1`, `2` or `12`. For `-` use [`TalonChoice::untaken`].
+impl FromStr for TalonChoice {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(TalonChoice::Upper),
+            "2" => Ok(TalonChoice::Lower),
+            "12" => Ok(TalonChoice::Both),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for TalonChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TalonChoice::Upper => write!(f, "1"),
+            TalonChoice::Lower => write!(f, "2"),
+            TalonChoice::Both => write!(f, "12"),
+            TalonChoice::None | TalonChoice::ToOpponents => write!(f, "-"),
+        }
+    }
+}
+
+impl GameState {
+    /// Whether no card has been played yet, which is when the talon is exchanged.
+    pub fn before_first_stich(&self) -> bool {
+        self.last_stich.is_none() && self.stich.is_empty()
+    }
+
+    /// Number of talon cards `player` took and still has to lay down.
+    pub fn cards_to_lay_down(&self, player: usize) -> usize {
+        if !self.before_first_stich() {
+            return 0;
+        }
+        self.players[player]
+            .hand
+            .cards
+            .len()
+            .saturating_sub(HAND_SIZE)
+    }
+
+    /// Checks that `player` may lay down `cards` after taking the talon.
+    ///
+    /// Unknown cards are taken to be neither kings nor Tarock, since Tarock have to be laid down
+    /// openly. Unknown cards of the hand are not counted as cards that could have been laid down
+    /// instead of Tarock.
+    pub fn check_lay_down(&self, player: usize, cards: CardSet) -> Result<(), &'static str> {
+        let required = self.cards_to_lay_down(player);
+        if required == 0 {
+            return Err("the player has no talon cards to lay down");
+        }
+        let calls = &self.players[player].calls;
+        if calls.typ.is_some_and(|typ| typ.takes_whole_talon())
+            && self.talon.iter().any(|half| !half.cards.is_empty())
+        {
+            return Err("the whole talon has to be taken first");
+        }
+        if cards.len() != required {
+            return Err("as many cards as taken from the talon have to be laid down");
+        }
+        if cards
+            .iter()
+            .any(|card| card.rank() == Some(Rank::König) || card.is_trullstück())
+        {
+            return Err("kings and Trullstücke cannot be laid down");
+        }
+
+        let tarock = cards.iter().filter(|card| card.is_tarock()).count();
+        let others = self.players[player]
+            .hand
+            .cards
+            .iter()
+            .filter(|card| !card.is_tarock() && card.rank() != Some(Rank::König))
+            .count();
+        if tarock > required.saturating_sub(others) {
+            return Err("Tarock can only be laid down without enough other cards");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::model::card::Card;
+
+    // Player 1 took the upper talon half in a Rufer
+    const TALON_TAKEN: &str = "/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKPXKX10X9/#............/#............/#............/# R1XK-1 - /// - -";
+
+    fn lay_down(taf: &str, cards: &[Card]) -> Result<(), &'static str> {
+        GameState::from_str(taf)
+            .unwrap()
+            .check_lay_down(0, cards.iter().copied().collect())
+    }
+
+    #[test]
+    fn talon_choice() {
+        assert_eq!(TalonChoice::from_str("1"), Ok(TalonChoice::Upper));
+        assert_eq!(TalonChoice::from_str("12"), Ok(TalonChoice::Both));
+        assert_eq!(TalonChoice::from_str("-"), Err(()));
+        for choice in [TalonChoice::Upper, TalonChoice::Lower, TalonChoice::Both] {
+            assert_eq!(TalonChoice::from_str(&choice.to_string()), Ok(choice));
+        }
+        assert_eq!(TalonChoice::ToOpponents.to_string(), "-");
+
+        assert_eq!(TalonChoice::untaken(GameType::R), TalonChoice::None);
+        assert_eq!(TalonChoice::untaken(GameType::B), TalonChoice::None);
+        assert_eq!(TalonChoice::untaken(GameType::SD), TalonChoice::ToOpponents);
+        assert_eq!(TalonChoice::Lower.half(), Some(1));
+        assert_eq!(TalonChoice::Both.half(), None);
+        assert!(TalonChoice::Both.fits(GameType::S));
+        assert!(!TalonChoice::Both.fits(GameType::D));
+        assert!(!TalonChoice::ToOpponents.fits(GameType::R));
+
+        let game_state = GameState::from_str(
+            ".../...#............/#............/#............/#............/# SR1HK-- - /// - -",
+        )
+        .unwrap();
+        assert_eq!(
+            game_state.players[0].calls.taken_talon,
+            TalonChoice::ToOpponents
+        );
+    }
+
+    #[test]
+    fn lay_down_count() {
+        let game_state = GameState::from_str(TALON_TAKEN).unwrap();
+        assert!(game_state.before_first_stich());
+        assert_eq!(game_state.cards_to_lay_down(0), 3);
+        assert_eq!(game_state.cards_to_lay_down(1), 0);
+
+        assert_eq!(
+            lay_down(TALON_TAKEN, &[Card::HD, Card::X10, Card::X9]),
+            Ok(())
+        );
+        assert_eq!(
+            lay_down(TALON_TAKEN, &[Card::X10, Card::X9]),
+            Err("as many cards as taken from the talon have to be laid down")
+        );
+        assert_eq!(
+            GameState::from_str(TALON_TAKEN)
+                .unwrap()
+                .check_lay_down(1, CardSet::from_iter([Card::UNKNOWN; 3])),
+            Err("the player has no talon cards to lay down")
+        );
+    }
+
+    #[test]
+    fn lay_down_kings_and_trull() {
+        assert_eq!(
+            lay_down(TALON_TAKEN, &[Card::HK, Card::X10, Card::X9]),
+            Err("kings and Trullstücke cannot be laid down")
+        );
+        assert_eq!(
+            lay_down(TALON_TAKEN, &[Card::T1, Card::X10, Card::X9]),
+            Err("kings and Trullstücke cannot be laid down")
+        );
+    }
+
+    #[test]
+    fn lay_down_tarock() {
+        assert_eq!(
+            lay_down(TALON_TAKEN, &[Card::T3, Card::X10, Card::X9]),
+            Err("Tarock can only be laid down without enough other cards")
+        );
+
+        // Besides the kings, player 1 only holds two colour cards
+        let forced = "/H4H3H2#HKXKT1T3T5T6T7T8T9T10T11T12X10X9T13/#............/#............/#............/# R1XK-1 - /// - -";
+        assert_eq!(lay_down(forced, &[Card::T3, Card::X10, Card::X9]), Ok(()));
+        assert_eq!(
+            lay_down(forced, &[Card::T3, Card::T5, Card::X9]),
+            Err("Tarock can only be laid down without enough other cards")
+        );
+    }
+}
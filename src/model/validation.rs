@@ -0,0 +1,630 @@
+//! Consistency checks of a whole game state.
+//!
+//! Parsing only checks each pile on its own. [`GameState::validate`] checks how the piles and
+//! the game group fit together.
+
+use std::fmt::Display;
+
+use super::card::{Card, Rank, NUM_CARDS};
+use super::card_set::CardSet;
+use super::game::{GameState, HAND_SIZE, NUM_PLAYERS, TALON_HALF_SIZE};
+use super::game_type::GameType;
+use super::pile::Pile;
+use super::stich::STICH_SIZE;
+use super::talon::TalonChoice;
+
+/// A rule a game state breaks.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Violation {
+    /// A known card lies in two piles.
+    DuplicateCard { card: Card, piles: [Pile; 2] },
+    /// All piles together do not hold the 54 cards of the deck.
+    CardCount { found: usize },
+    TooManyCards {
+        pile: Pile,
+        found: usize,
+        max: usize,
+    },
+    /// A hand does not hold the number of cards left after the stiche played so far.
+    HandSize {
+        player: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A player is noted as having made the last stich although no stich has been made.
+    LastStich { player: usize },
+    /// A talon half that is neither complete nor taken.
+    TalonSize { half: usize, found: usize },
+    /// Several players play a game that is only ever played by one.
+    MultipleDeclarers { typ: GameType },
+    /// Calls of a player who does not play a game contain a called king or a taken talon.
+    NotDeclarer { player: usize },
+    /// A game that calls a king is played without one.
+    MissingCalledKing { player: usize },
+    /// A king is called although the game does not call one.
+    UnexpectedCalledKing { player: usize },
+    /// The called card is not a king.
+    InvalidCalledKing { player: usize, card: Card },
+    /// The taken talon does not fit the game.
+    InvalidTakenTalon { player: usize, talon: TalonChoice },
+    /// A teammate is noted although the called king has not been played.
+    UnplayedCalledKing { player: usize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::DuplicateCard { card, piles } => {
+                write!(f, "`{card}` lies in {} and in {}", piles[0], piles[1])
+            }
+            Violation::CardCount { found } => {
+                write!(f, "expected {NUM_CARDS} cards, found {found}")
+            }
+            Violation::TooManyCards { pile, found, max } => {
+                write!(f, "{pile} holds {found} cards, at most {max} are allowed")
+            }
+            Violation::HandSize {
+                player,
+                expected,
+                found,
+            } => write!(
+                f,
+                "hand of player {} holds {found} cards, expected {expected}",
+                player + 1
+            ),
+            Violation::LastStich { player } => write!(
+                f,
+                "player {} made the last stich but no stich has been made",
+                player + 1
+            ),
+            Violation::TalonSize { half, found } => write!(
+                f,
+                "{} holds {found} cards, expected 0 or {TALON_HALF_SIZE}",
+                Pile::Talon(*half)
+            ),
+            Violation::MultipleDeclarers { typ } => {
+                write!(f, "`{typ}` cannot be played by several players")
+            }
+            Violation::NotDeclarer { player } => write!(
+                f,
+                "player {} calls a king or takes a talon without playing a game",
+                player + 1
+            ),
+            Violation::MissingCalledKing { player } => {
+                write!(f, "game of player {} needs a called king", player + 1)
+            }
+            Violation::UnexpectedCalledKing { player } => {
+                write!(f, "game of player {} does not call a king", player + 1)
+            }
+            Violation::InvalidCalledKing { player, card } => {
+                write!(
+                    f,
+                    "player {} calls `{card}` which is not a king",
+                    player + 1
+                )
+            }
+            Violation::InvalidTakenTalon { player, talon } => write!(
+                f,
+                "game of player {} cannot take talon `{talon}`",
+                player + 1
+            ),
+            Violation::UnplayedCalledKing { player } => write!(
+                f,
+                "player {} has a teammate but the called king has not been played",
+                player + 1
+            ),
+        }
+    }
+}
+
+impl GameState {
+    /// Checks that the piles and the game group describe a state a game can actually be in.
+    ///
+    /// The current stich cannot hold more than four cards by construction, so it is only checked
+    /// together with the other piles. Returns all violations found, an empty list if the state is
+    /// consistent.
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_cards(&mut violations);
+        self.validate_hand_sizes(&mut violations);
+        self.validate_talon(&mut violations);
+        self.validate_game(&mut violations);
+        violations
+    }
+
+    fn validate_cards(&self, violations: &mut Vec<Violation>) {
+        let mut seen: Vec<(Pile, CardSet)> = Vec::with_capacity(Pile::ALL.len());
+        let mut total = 0;
+        for pile in Pile::ALL {
+            let cards = self.pile(pile);
+            total += cards.len();
+
+            let max = self.pile_capacity(pile);
+            if cards.len() > max {
+                violations.push(Violation::TooManyCards {
+                    pile,
+                    found: cards.len(),
+                    max,
+                });
+            }
+
+            for (other, other_cards) in &seen {
+                for card in cards.intersection(other_cards) {
+                    violations.push(Violation::DuplicateCard {
+                        card,
+                        piles: [*other, pile],
+                    });
+                }
+            }
+            seen.push((pile, cards));
+        }
+
+        if total != NUM_CARDS {
+            violations.push(Violation::CardCount { found: total });
+        }
+    }
+
+    /// Every player who has not yet played into the current stich holds as many cards as there
+    /// are stiche left, everyone who has holds one card less.
+    ///
+    /// Before the first stich has been made, the declarer additionally holds the talon cards they
+    /// took until they lay down as many cards onto their stiche, all in one move. Afterwards the
+    /// stiche hold the cards of the stiche made and every talon card that left the talon, either
+    /// laid down in exchange or handed over to the opponents.
+    fn validate_hand_sizes(&self, violations: &mut Vec<Violation>) {
+        let played: [usize; NUM_PLAYERS] = std::array::from_fn(|player| {
+            let position = (player + STICH_SIZE - self.leader()) % STICH_SIZE;
+            usize::from(position < self.stich.len())
+        });
+        let sizes: [usize; NUM_PLAYERS] =
+            std::array::from_fn(|player| self.players[player].hand.cards.len() + played[player]);
+
+        let expected: [usize; NUM_PLAYERS] = match self.last_stich {
+            None => {
+                let taken = self.taken_talon_cards();
+                std::array::from_fn(|player| {
+                    let calls = &self.players[player].calls;
+                    if calls.typ.is_some() && self.players[player].stiche.cards.is_empty() {
+                        HAND_SIZE + taken
+                    } else {
+                        HAND_SIZE
+                    }
+                })
+            }
+            Some(player) => {
+                let remaining: usize = self.talon.iter().map(|talon| talon.cards.len()).sum();
+                let on_stiche: usize = self
+                    .players
+                    .iter()
+                    .map(|player| player.stiche.cards.len())
+                    .sum();
+                let made = on_stiche
+                    .saturating_sub((2 * TALON_HALF_SIZE).saturating_sub(remaining))
+                    / STICH_SIZE;
+                let expected = HAND_SIZE.saturating_sub(made);
+                if made == 0 || self.players[player].stiche.cards.is_empty() {
+                    violations.push(Violation::LastStich { player });
+                }
+                [expected; NUM_PLAYERS]
+            }
+        };
+
+        for player in 0..NUM_PLAYERS {
+            if sizes[player] != expected[player] {
+                violations.push(Violation::HandSize {
+                    player,
+                    expected: expected[player].saturating_sub(played[player]),
+                    found: sizes[player] - played[player],
+                });
+            }
+        }
+    }
+
+    /// Number of talon cards the declarers took into their hands, assuming no stich has been made.
+    ///
+    /// Talon cards that are neither in the talon nor on the stiche of an opponent were taken.
+    fn taken_talon_cards(&self) -> usize {
+        let remaining: usize = self.talon.iter().map(|talon| talon.cards.len()).sum();
+        let given: usize = self
+            .players
+            .iter()
+            .filter(|player| player.calls.typ.is_none())
+            .map(|player| player.stiche.cards.len())
+            .sum();
+        (2 * TALON_HALF_SIZE).saturating_sub(remaining + given)
+    }
+
+    fn validate_talon(&self, violations: &mut Vec<Violation>) {
+        for (half, talon) in self.talon.iter().enumerate() {
+            let found = talon.cards.len();
+            if found != 0 && found != TALON_HALF_SIZE {
+                violations.push(Violation::TalonSize { half, found });
+            }
+        }
+    }
+
+    fn validate_game(&self, violations: &mut Vec<Violation>) {
+        let declarers = self
+            .players
+            .iter()
+            .filter_map(|player| player.calls.typ)
+            .collect::<Vec<_>>();
+        if declarers.len() > 1 {
+            for typ in declarers {
+                if !typ.allows_multiple_declarers() {
+                    violations.push(Violation::MultipleDeclarers { typ });
+                }
+            }
+        }
+
+        for (player, calls) in self.players.iter().map(|player| player.calls).enumerate() {
+            let Some(typ) = calls.typ else {
+                if calls.called_king.is_some()
+                    || calls.teammate.is_some()
+                    || calls.taken_talon != TalonChoice::None
+                {
+                    violations.push(Violation::NotDeclarer { player });
+                }
+                continue;
+            };
+
+            match calls.called_king {
+                None if typ.calls_king() => {
+                    violations.push(Violation::MissingCalledKing { player })
+                }
+                Some(_) if !typ.calls_king() => {
+                    violations.push(Violation::UnexpectedCalledKing { player })
+                }
+                Some(card) if card.rank() != Some(Rank::König) || card.is_tarock() => {
+                    violations.push(Violation::InvalidCalledKing { player, card })
+                }
+                _ => {}
+            }
+
+            if calls.teammate.is_some() {
+                let unplayed = calls.called_king.is_none_or(|king| {
+                    self.talon.iter().any(|half| half.cards.contains(king))
+                        || self
+                            .players
+                            .iter()
+                            .any(|player| player.hand.cards.contains(king))
+                });
+                if unplayed {
+                    violations.push(Violation::UnplayedCalledKing { player });
+                }
+            }
+
+            if !calls.taken_talon.fits(typ) {
+                violations.push(Violation::InvalidTakenTalon {
+                    player,
+                    talon: calls.taken_talon,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SPEC_EXAMPLE: &str = "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 - 1K/T// - R12";
+
+    fn state(taf: &str) -> GameState {
+        GameState::from_str(taf).unwrap()
+    }
+
+    #[test]
+    fn validate_valid() {
+        assert_eq!(state(SPEC_EXAMPLE).validate(), vec![]);
+
+        // Second stich, player 3 made the first one and players 3 and 4 have played
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT3T5T6K1K2K3K4KBKP/#.........../#........../HBH1T1T2#........../#T22T21 R1XK-1 3 /// - -",
+        );
+        assert_eq!(game_state.validate(), vec![]);
+    }
+
+    #[test]
+    fn validate_duplicate_card() {
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#HK.........../#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::DuplicateCard {
+                card: Card::HK,
+                piles: [Pile::Hand(0), Pile::Hand(1)],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_card_count() {
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/.#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::CardCount { found: 55 }]
+        );
+
+        let game_state = state("XKX10X9/H4H3H2#/#/#/#/# R1XK-1 - /// - -");
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::CardCount { found: 6 },
+                Violation::HandSize {
+                    player: 0,
+                    expected: 12,
+                    found: 0,
+                },
+                Violation::HandSize {
+                    player: 1,
+                    expected: 12,
+                    found: 0,
+                },
+                Violation::HandSize {
+                    player: 2,
+                    expected: 12,
+                    found: 0,
+                },
+                Violation::HandSize {
+                    player: 3,
+                    expected: 12,
+                    found: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_hand_sizes() {
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#.........../#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::CardCount { found: 53 },
+                Violation::HandSize {
+                    player: 1,
+                    expected: 12,
+                    found: 11,
+                },
+            ]
+        );
+
+        // Player 1 led T22 but still holds twelve cards
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/#T22 R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::CardCount { found: 55 },
+                Violation::HandSize {
+                    player: 0,
+                    expected: 11,
+                    found: 12,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_hand_sizes_after_stiche() {
+        // Two stiche have been made, so every hand holds ten cards
+        let game_state = state(
+            "XKX10X9/H4H3H2#........../T2T4T7T8T9T10T11T12#........../#........../#........../# B1--- 1 /// - -",
+        );
+        assert_eq!(game_state.validate(), vec![]);
+
+        // Two hands hold a card too many, the other two are right
+        let game_state = state(
+            "XKX10X9/H4H3H2#.........../T2T4T7T8T9T10T11T12#.........../#........../#........../# B1--- 1 /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::CardCount { found: 56 },
+                Violation::HandSize {
+                    player: 0,
+                    expected: 10,
+                    found: 11,
+                },
+                Violation::HandSize {
+                    player: 1,
+                    expected: 10,
+                    found: 11,
+                },
+            ]
+        );
+
+        // The declarer laid down three cards for the upper talon half
+        let game_state = state(
+            "/H4H3H2#........../HDX10X9T2T4T7T8T9T10T11T12#........../#........../#........../# R1XK-1 1 /// - -",
+        );
+        assert_eq!(game_state.validate(), vec![]);
+
+        // An overfull talon is reported without affecting the hand sizes
+        let mut game_state = state(
+            "XKX10X9/H4H3H2#........../T2T4T7T8T9T10T11T12#........../#........../#........../# B1--- 1 /// - -",
+        );
+        game_state.talon[0].cards.insert(Card::X8);
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::TooManyCards {
+                    pile: Pile::Talon(0),
+                    found: 4,
+                    max: 3,
+                },
+                Violation::CardCount { found: 55 },
+                Violation::TalonSize { half: 0, found: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_hand_sizes_talon() {
+        // Player 1 took the upper talon
+        let game_state = state(
+            "/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKPXKX10X9/#............/#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(game_state.validate(), vec![]);
+
+        // And laid down three cards
+        let game_state = state(
+            "/H4H3H2#HKT1T3T5T6K1K2K3K4KBKPXK/HDX10X9#............/#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(game_state.validate(), vec![]);
+
+        // All cards are laid down at once, never only some of them
+        let game_state = state(
+            "/H4H3H2#HKT1T3T5T6K1K2K3K4KBKPXKX10/HDX9#............/#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::HandSize {
+                player: 0,
+                expected: 12,
+                found: 13,
+            }]
+        );
+
+        // Player 2 took the talon instead of the declarer
+        let game_state = state(
+            "/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............XKX10X9/#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::HandSize {
+                    player: 0,
+                    expected: 15,
+                    found: 12,
+                },
+                Violation::HandSize {
+                    player: 1,
+                    expected: 12,
+                    found: 15,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_last_stich() {
+        let game_state = state(
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/# R1XK-1 2 /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::LastStich { player: 1 }]
+        );
+    }
+
+    #[test]
+    fn validate_talon() {
+        let game_state = state(
+            "XKX10/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/X9#............/#............/#............/# R1XK-1 - /// - -",
+        );
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::TalonSize { half: 0, found: 2 }]
+        );
+    }
+
+    #[test]
+    fn validate_game() {
+        let cards =
+            "XKX10X9/H4H3H2#HKHDT1T3T5T6K1K2K3K4KBKP/#............/#............/#............/#";
+        let violations = |game: &str| state(&format!("{cards} {game} - /// - -")).validate();
+
+        assert_eq!(violations("R1XK-1"), vec![]);
+        assert_eq!(violations("D1--2"), vec![]);
+        assert_eq!(violations("S1--12"), vec![]);
+        assert_eq!(violations("PB11PB22---"), vec![]);
+        assert_eq!(violations("T4---"), vec![]);
+
+        assert_eq!(
+            violations("R1--1"),
+            vec![Violation::MissingCalledKing { player: 0 }]
+        );
+        assert_eq!(
+            violations("D1XK-1"),
+            vec![Violation::UnexpectedCalledKing { player: 0 }]
+        );
+        assert_eq!(
+            violations("B1--1"),
+            vec![Violation::InvalidTakenTalon {
+                player: 0,
+                talon: TalonChoice::Upper
+            }]
+        );
+        assert_eq!(
+            violations("R1XK-12"),
+            vec![Violation::InvalidTakenTalon {
+                player: 0,
+                talon: TalonChoice::Both
+            }]
+        );
+        assert_eq!(
+            violations("B1B2---"),
+            vec![
+                Violation::MultipleDeclarers { typ: GameType::B },
+                Violation::MultipleDeclarers { typ: GameType::B },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_called_king() {
+        let mut game_state = state(SPEC_EXAMPLE);
+        game_state.players[0].calls.called_king = Some(Card::XD);
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::InvalidCalledKing {
+                player: 0,
+                card: Card::XD
+            }]
+        );
+
+        let mut game_state = state(SPEC_EXAMPLE);
+        game_state.players[2].calls.called_king = Some(Card::XK);
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::NotDeclarer { player: 2 }]
+        );
+
+        // The Kreuz König still lies in the talon
+        let mut game_state = state(SPEC_EXAMPLE);
+        game_state.players[0].calls.teammate = Some(1);
+        assert_eq!(
+            game_state.validate(),
+            vec![Violation::UnplayedCalledKing { player: 0 }]
+        );
+    }
+
+    #[test]
+    fn validate_too_many_cards() {
+        let mut game_state = state(SPEC_EXAMPLE);
+        let card = game_state.talon[1].cards.iter().next().unwrap();
+        game_state.talon[1].cards.remove(card);
+        game_state.talon[0].cards.insert(card);
+        assert_eq!(
+            game_state.validate(),
+            vec![
+                Violation::TooManyCards {
+                    pile: Pile::Talon(0),
+                    found: 4,
+                    max: 3,
+                },
+                Violation::TalonSize { half: 0, found: 4 },
+                Violation::TalonSize { half: 1, found: 2 },
+            ]
+        );
+    }
+}